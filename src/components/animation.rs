@@ -0,0 +1,175 @@
+use super::Facing;
+use amethyst::{
+    assets::PrefabData,
+    derive::PrefabData,
+    ecs::{Component, DenseVecStorage, Entity, WriteStorage},
+    Error,
+};
+use nalgebra::base::Vector3;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// What a character is doing, which picks the clip it plays. Which way
+/// it walks is left to its `Facing`.
+#[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum Motion {
+    Idle,
+    /// Walking across the street
+    Walk,
+    /// Walking up or down the street
+    WalkUp,
+    /// In the air after jumping
+    Jump,
+}
+impl Default for Motion {
+    fn default() -> Self {
+        Motion::Idle
+    }
+}
+impl Motion {
+    /// The motion whose clip to play instead when there is no clip for
+    /// this one
+    pub fn fallback(&self) -> Option<Motion> {
+        match self {
+            Motion::Idle => None,
+            Motion::WalkUp => Some(Motion::Walk),
+            Motion::Walk | Motion::Jump => Some(Motion::Idle),
+        }
+    }
+}
+
+/// What a clip does once it reaches its last frame
+#[derive(Copy, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum LoopMode {
+    /// Start again from the first frame
+    Loop,
+    /// Stay on the last frame
+    Once,
+    /// Play backwards to the first frame, then forwards again
+    PingPong,
+}
+impl Default for LoopMode {
+    fn default() -> Self {
+        LoopMode::Loop
+    }
+}
+
+/// A sequence of sprites played one after the other
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct AnimationClip {
+    /// The name of the sprite sheet the frames are on
+    pub sprite_sheet: String,
+    /// The sprite sheet to draw from instead while facing left, for
+    /// characters which look different each way
+    #[serde(default)]
+    pub left_sprite_sheet: Option<String>,
+    /// The sprite numbers to show, in order
+    pub frames: Vec<usize>,
+    /// How long each frame is shown for, in seconds
+    pub frame_duration: f32,
+    #[serde(default)]
+    pub loop_mode: LoopMode,
+}
+
+impl AnimationClip {
+    /// The sprite number to show once the clip has played for `elapsed`
+    /// seconds
+    pub fn frame_at(&self, elapsed: f32) -> Option<usize> {
+        let count = self.frames.len();
+        let step = if self.frame_duration > 0.0 {
+            (elapsed / self.frame_duration) as usize
+        } else {
+            0
+        };
+        let index = match self.loop_mode {
+            LoopMode::Loop => step % count.max(1),
+            LoopMode::Once => step.min(count.saturating_sub(1)),
+            LoopMode::PingPong if count > 1 => {
+                let period = 2 * count - 2;
+                let index = step % period;
+                if index < count {
+                    index
+                } else {
+                    period - index
+                }
+            }
+            LoopMode::PingPong => 0,
+        };
+        self.frames.get(index).cloned()
+    }
+}
+
+/// Animates a character's sprite, picking a clip from how it is moving.
+/// Motions without a clip of their own play their fallback's clip, so a
+/// character without a `WalkUp` clip plays `Walk`, and one without that
+/// plays `Idle`.
+#[derive(Clone, Debug, Deserialize, PrefabData, Serialize)]
+#[prefab(Component)]
+#[serde(deny_unknown_fields)]
+pub struct Animation {
+    pub clips: HashMap<Motion, AnimationClip>,
+    #[serde(skip)]
+    motion: Motion,
+    /// How long the current clip has been playing, in seconds
+    #[serde(skip)]
+    elapsed: f32,
+    /// Where the character was last frame, to work out how it moved
+    #[serde(skip)]
+    last_position: Option<Vector3<f32>>,
+}
+
+impl Animation {
+    pub fn motion(&self) -> Motion {
+        self.motion
+    }
+
+    /// Switch to the clip for `motion`, starting it from the beginning if
+    /// it wasn't already playing
+    pub fn set_motion(&mut self, motion: Motion) {
+        if motion != self.motion {
+            self.motion = motion;
+            self.elapsed = 0.0;
+        }
+    }
+
+    pub fn advance(&mut self, seconds: f32) {
+        self.elapsed += seconds;
+    }
+
+    /// Record where the character is now, returning how far it moved since
+    /// the last time
+    pub fn track_position(&mut self, position: Vector3<f32>) -> Vector3<f32> {
+        let movement = self
+            .last_position
+            .map_or_else(Vector3::zeros, |last| position - last);
+        self.last_position = Some(position);
+        movement
+    }
+
+    /// The clip currently playing
+    pub fn clip(&self) -> Option<&AnimationClip> {
+        let mut motion = Some(self.motion);
+        while let Some(current) = motion {
+            if let Some(clip) = self.clips.get(&current) {
+                return Some(clip);
+            }
+            motion = current.fallback();
+        }
+        None
+    }
+
+    /// The sprite sheet and sprite number to draw the character with
+    pub fn sprite(&self, facing: Facing) -> Option<(&str, usize)> {
+        let clip = self.clip()?;
+        let sprite_sheet = match (facing, &clip.left_sprite_sheet) {
+            (Facing::Left, Some(left_sprite_sheet)) => left_sprite_sheet,
+            _ => &clip.sprite_sheet,
+        };
+        Some((sprite_sheet.as_str(), clip.frame_at(self.elapsed)?))
+    }
+}
+
+impl Component for Animation {
+    type Storage = DenseVecStorage<Self>;
+}
@@ -0,0 +1,52 @@
+use amethyst::ecs::{Component, DenseVecStorage, Entity};
+
+/// The obstacles a character is currently touching, along with which of
+/// them it started or stopped touching this frame
+#[derive(Clone, Debug, Default)]
+pub struct Contacts {
+    touching: Vec<Entity>,
+    entered: Vec<Entity>,
+    exited: Vec<Entity>,
+}
+
+impl Contacts {
+    /// Every obstacle currently being touched
+    pub fn touching(&self) -> &[Entity] {
+        &self.touching
+    }
+
+    /// Obstacles which started being touched this frame
+    pub fn entered(&self) -> &[Entity] {
+        &self.entered
+    }
+
+    /// Obstacles which stopped being touched this frame
+    pub fn exited(&self) -> &[Entity] {
+        &self.exited
+    }
+
+    pub fn is_touching(&self, obstacle: Entity) -> bool {
+        self.touching.contains(&obstacle)
+    }
+
+    /// Replace the obstacles being touched, working out which ones were
+    /// entered and exited since the last update
+    pub fn update(&mut self, touching: Vec<Entity>) {
+        self.entered = touching
+            .iter()
+            .filter(|obstacle| !self.touching.contains(obstacle))
+            .cloned()
+            .collect();
+        self.exited = self
+            .touching
+            .iter()
+            .filter(|obstacle| !touching.contains(obstacle))
+            .cloned()
+            .collect();
+        self.touching = touching;
+    }
+}
+
+impl Component for Contacts {
+    type Storage = DenseVecStorage<Self>;
+}
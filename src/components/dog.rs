@@ -6,26 +6,41 @@ use amethyst::{
 };
 use serde::{Deserialize, Serialize};
 
+/// A dog walking back and forth across the street. Its hitbox lives on
+/// its `Obstacle`.
 #[derive(Copy, Clone, Debug, Deserialize, PrefabData, Serialize)]
 #[prefab(Component)]
 pub struct Dog {
-    pub is_player_touching: bool,
-    pub is_enemy_touching: bool,
-    pub height: f32,
-    pub width: f32,
+    /// Walking speed, in units per second
     pub speed: f32,
+    /// How much longer the dog is busy with something the player threw, in
+    /// seconds
+    #[serde(skip)]
+    distracted: f32,
 }
 
 impl Dog {
     pub fn new() -> Self {
         Dog {
-            is_player_touching: false,
-            is_enemy_touching: false,
-            height: 80.0f32,
-            width: 80.0f32,
-            speed: 5.0f32,
+            speed: 300.0f32,
+            distracted: 0.0,
         }
     }
+
+    /// Keep the dog busy for `seconds`, during which it stays put and
+    /// doesn't get in anyone's way
+    pub fn distract(&mut self, seconds: f32) {
+        self.distracted = self.distracted.max(seconds);
+    }
+
+    pub fn is_distracted(&self) -> bool {
+        self.distracted > 0.0
+    }
+
+    /// Count down the distraction by `seconds`
+    pub fn tick(&mut self, seconds: f32) {
+        self.distracted = (self.distracted - seconds).max(0.0);
+    }
 }
 
 impl Default for Dog {
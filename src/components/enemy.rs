@@ -1,45 +1,212 @@
+use super::StatusEffects;
 use amethyst::{
     assets::PrefabData,
     derive::PrefabData,
     ecs::{Component, DenseVecStorage, Entity, WriteStorage},
     Error,
 };
+use nalgebra::base::Vector3;
 use serde::{Deserialize, Serialize};
 
 const RUBBER_BAND_CUTOFF: f32 = 200.0;
-const RUBBER_BAND_COEFFICIENT: f32 = 0.01;
+// extra units per second of speed for every unit of distance past the cutoff
+const RUBBER_BAND_COEFFICIENT: f32 = 0.6;
+/// How far from where the player was last seen the enemy circles while
+/// searching for them
+const SEARCH_RADIUS: f32 = 150.0;
+/// How fast the enemy circles while searching, in radians per second
+const SEARCH_TURN_SPEED: f32 = 1.5;
+/// How much slower than usual the enemy moves while searching
+const SEARCH_SPEED_FRACTION: f32 = 0.6;
+/// How long the enemy searches before following the sound of the player
+/// instead, in seconds
+const SEARCH_SECONDS: f32 = 6.0;
+/// How long a noise like a landed phone holds the enemy's attention, in
+/// seconds
+const DISTRACTED_SECONDS: f32 = 2.5;
+
+/// What the enemy is doing about the player
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum EnemyState {
+    /// Running at the player, who it can see
+    Chase,
+    /// Looking around where it last saw the player
+    Search,
+    /// Going to check out a noise, like a thrown phone landing
+    Distracted,
+    /// Knocked out and unable to do anything
+    Stunned,
+}
+impl Default for EnemyState {
+    fn default() -> Self {
+        EnemyState::Chase
+    }
+}
 
 #[derive(Copy, Clone, Debug, Deserialize, PrefabData, Serialize)]
 #[prefab(Component)]
 pub struct Enemy {
+    /// Movement speed before any status effects or rubber banding, in
+    /// units per second
     pub speed: f32,
+    /// How much the enemy's status effects multiply their speed by
+    #[serde(skip, default = "super::status::unaffected")]
+    speed_multiplier: f32,
+    /// Where the enemy last saw the player
+    #[serde(skip)]
+    last_seen: Option<Vector3<f32>>,
+    /// How long the enemy has been circling where the player was last
+    /// seen, in seconds, once it gets there
+    #[serde(skip)]
+    search_time: Option<f32>,
+    #[serde(skip)]
+    state: EnemyState,
+    /// How long the enemy has been in its current state, in seconds
+    #[serde(skip)]
+    state_time: f32,
+    /// Where the noise the enemy is checking out came from
+    #[serde(skip)]
+    distraction: Option<Vector3<f32>>,
+    /// Which way the enemy is looking, if it has looked anywhere yet
+    #[serde(skip)]
+    heading: Option<Vector3<f32>>,
 }
 impl Enemy {
     pub fn new() -> Self {
-        Enemy { speed: 9.0f32 }
+        Enemy {
+            speed: 540.0f32,
+            speed_multiplier: 1.0,
+            last_seen: None,
+            search_time: None,
+            state: EnemyState::Chase,
+            state_time: 0.0,
+            distraction: None,
+            heading: None,
+        }
+    }
+
+    /// Resolve the enemy's status effects into their speed
+    pub fn apply_effects(&mut self, effects: &StatusEffects) {
+        self.speed_multiplier = effects.speed_multiplier();
+    }
+
+    /// Movement speed when `distance` away from the player, which is
+    /// faster the further behind the enemy falls
+    pub fn speed(&self, distance: f32) -> f32 {
+        (self.speed + (distance - RUBBER_BAND_CUTOFF).max(0.0) * RUBBER_BAND_COEFFICIENT)
+            * self.speed_multiplier
     }
-    //slows enemy down
-    pub fn slow_down(&mut self) {
-        self.speed = 4.5f32;
+
+    /// Movement speed while searching for the player
+    pub fn search_speed(&self) -> f32 {
+        self.speed * SEARCH_SPEED_FRACTION * self.speed_multiplier
     }
 
-    //returns enemy back to normal
-    pub fn normal_speed(&mut self) {
-        self.speed = 9.0f32;
+    /// Movement speed when `distance` away from the player, going by what
+    /// the enemy is doing
+    pub fn move_speed(&self, distance: f32) -> f32 {
+        match self.state {
+            EnemyState::Chase => self.speed(distance),
+            _ => self.search_speed(),
+        }
     }
 
-    //doubles speed
-    pub fn speed_up(&mut self) {
-        self.speed = 18.0f32;
+    pub fn state(&self) -> EnemyState {
+        self.state
     }
 
-    //Stops enemy
-    pub fn stop(&mut self) {
-        self.speed = 0.0f32;
+    fn set_state(&mut self, state: EnemyState) {
+        if state != self.state {
+            log::debug!("Enemy went from {:?} to {:?}", self.state, state);
+            self.state = state;
+            self.state_time = 0.0;
+        }
     }
 
-    pub fn speed(&self, distance: f32) -> f32 {
-        self.speed + (distance - RUBBER_BAND_CUTOFF).max(0.0) * RUBBER_BAND_COEFFICIENT
+    /// Which way the enemy is looking, or zero if it hasn't looked
+    /// anywhere yet
+    pub fn heading(&self) -> Vector3<f32> {
+        self.heading.unwrap_or_else(Vector3::zeros)
+    }
+
+    /// Look along `direction`, such as the way the enemy is moving
+    pub fn look(&mut self, direction: &Vector3<f32>) {
+        if direction.norm_squared() != 0.0 {
+            self.heading = Some(direction.normalize());
+        }
+    }
+
+    /// Decide what to do after `seconds` more, given where the enemy can
+    /// see the player, where it can hear them, and whether it is stunned.
+    /// Being stunned beats everything, and a distraction holds the enemy's
+    /// attention until it wears off, even with the player in sight. Once
+    /// the player is out of sight the enemy searches where it last saw
+    /// them, until it gives up and follows the sound of them instead.
+    pub fn think(
+        &mut self,
+        seen: Option<Vector3<f32>>,
+        heard: Option<Vector3<f32>>,
+        stunned: bool,
+        seconds: f32,
+    ) {
+        self.state_time += seconds;
+        if stunned {
+            self.set_state(EnemyState::Stunned);
+        } else if self.state == EnemyState::Distracted && self.state_time < DISTRACTED_SECONDS {
+            // still checking out the noise
+        } else if let Some(position) = seen {
+            self.spot(position);
+            self.set_state(EnemyState::Chase);
+        } else {
+            self.set_state(EnemyState::Search);
+            if self.last_seen.is_none() || self.state_time >= SEARCH_SECONDS {
+                if let Some(position) = heard {
+                    self.spot(position);
+                    self.state_time = 0.0;
+                }
+            }
+        }
+    }
+
+    /// Go and check out a noise at `position`, unless knocked out
+    pub fn distract(&mut self, position: Vector3<f32>) {
+        if self.state != EnemyState::Stunned {
+            self.distraction = Some(position);
+            self.set_state(EnemyState::Distracted);
+            self.state_time = 0.0;
+        }
+    }
+
+    /// Where to head from `position` after `seconds` more, going by what
+    /// the enemy is doing, or `None` to stay put
+    pub fn target(&mut self, position: &Vector3<f32>, seconds: f32) -> Option<Vector3<f32>> {
+        match self.state {
+            EnemyState::Chase => self.last_seen,
+            EnemyState::Search => self.search_target(position, seconds),
+            EnemyState::Distracted => self.distraction,
+            EnemyState::Stunned => None,
+        }
+    }
+
+    /// Remember that the player was just seen at `position`
+    pub fn spot(&mut self, position: Vector3<f32>) {
+        self.last_seen = Some(position);
+        self.search_time = None;
+    }
+
+    /// Where to head while the player is out of sight, after `seconds`
+    /// more searching from `position`. The enemy goes to where the player
+    /// was last seen, then circles around it.
+    pub fn search_target(&mut self, position: &Vector3<f32>, seconds: f32) -> Option<Vector3<f32>> {
+        let last_seen = self.last_seen?;
+        let search_time = match self.search_time {
+            Some(search_time) => search_time + seconds,
+            None if (last_seen - position).norm() > SEARCH_RADIUS => return Some(last_seen),
+            None => 0.0,
+        };
+        self.search_time = Some(search_time);
+        let angle = search_time * SEARCH_TURN_SPEED;
+        Some(last_seen + Vector3::new(angle.cos(), angle.sin(), 0.0) * SEARCH_RADIUS)
     }
 }
 impl Default for Enemy {
@@ -0,0 +1,53 @@
+use amethyst::{
+    assets::PrefabData,
+    derive::PrefabData,
+    ecs::{Component, DenseVecStorage, Entity, WriteStorage},
+    Error,
+};
+use nalgebra::base::Vector3;
+use serde::{Deserialize, Serialize};
+
+/// How much of a character's movement has to be sideways for it to turn
+/// around, so that walking up the street doesn't make it flicker
+const TURN_THRESHOLD: f32 = 0.25;
+
+/// Which way a character or dog is looking, which decides the sprite sheet
+/// its animation is drawn from
+#[derive(Copy, Clone, Debug, Deserialize, Eq, PartialEq, PrefabData, Serialize)]
+#[prefab(Component)]
+pub enum Facing {
+    Left,
+    Right,
+}
+
+impl Facing {
+    /// Turn to face the way `velocity` is heading, keeping the current
+    /// facing when moving mostly up or down
+    pub fn turn_towards(&mut self, velocity: &Vector3<f32>) {
+        if velocity.x.abs() > velocity.norm() * TURN_THRESHOLD {
+            *self = if velocity.x < 0.0 {
+                Facing::Left
+            } else {
+                Facing::Right
+            };
+        }
+    }
+
+    /// The direction this faces along the x axis
+    pub fn direction(self) -> f32 {
+        match self {
+            Facing::Left => -1.0,
+            Facing::Right => 1.0,
+        }
+    }
+}
+
+impl Default for Facing {
+    fn default() -> Self {
+        Facing::Right
+    }
+}
+
+impl Component for Facing {
+    type Storage = DenseVecStorage<Self>;
+}
@@ -6,19 +6,35 @@
 // };
 // use serde::{Deserialize, Serialize};
 
+mod animation;
 mod background;
-mod car;
+mod contacts;
 mod dog;
 mod enemy;
-mod mud;
+mod facing;
+mod obstacle;
 mod player;
+mod projectile;
+mod skateboard;
+mod status;
+mod steering;
+mod traffic;
+mod vision;
 
+pub use animation::{Animation, AnimationClip, LoopMode, Motion};
 pub use background::Background;
-pub use car::Car;
+pub use contacts::Contacts;
 pub use dog::Dog;
-pub use enemy::Enemy;
-pub use mud::Mud;
+pub use enemy::{Enemy, EnemyState};
+pub use facing::Facing;
+pub use obstacle::{Obstacle, ObstacleEffect};
 pub use player::Player;
+pub use projectile::Projectile;
+pub use skateboard::Skateboard;
+pub use status::{StatusEffect, StatusEffects, StatusKind};
+pub use steering::{Avoidance, Steering};
+pub use traffic::{TrafficCar, TrafficLane};
+pub use vision::Vision;
 
 // #[derive(Copy, Clone, Debug, Default, Deserialize, PrefabData, Serialize)]
 // #[prefab(Component)]
@@ -0,0 +1,157 @@
+use amethyst::{
+    assets::PrefabData,
+    derive::PrefabData,
+    ecs::{Component, DenseVecStorage, Entity, WriteStorage},
+    Error,
+};
+use nalgebra::base::Vector3;
+use serde::{Deserialize, Serialize};
+
+/// Something an obstacle does to a character touching it
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum ObstacleEffect {
+    /// Slows the character down
+    Slow,
+    /// Stops the character in place
+    Stop,
+    /// Knocks the character out for this many seconds
+    Stun(f32),
+    /// Keeps the player out of the obstacle's hitbox
+    Block,
+    /// Pushes the character away from the obstacle, in units per second
+    Knockback(f32),
+    /// Gets picked up by the player, speeding them up for this many seconds
+    Boost(f32),
+}
+
+/// Something in the street which gets in the characters' way. Each kind
+/// of obstacle is defined by a prefab in `assets/prefabs/obstacle`.
+#[derive(Clone, Debug, Deserialize, PrefabData, Serialize)]
+#[prefab(Component)]
+pub struct Obstacle {
+    /// The kind of obstacle, matching the name of its prefab
+    pub kind: String,
+    /// Distance from the centre of the obstacle to the top and bottom
+    /// of its hitbox
+    pub height: f32,
+    /// Distance from the centre of the obstacle to the sides of its hitbox
+    pub width: f32,
+    pub effects: Vec<ObstacleEffect>,
+}
+
+impl Obstacle {
+    pub fn new(kind: &str, width: f32, height: f32, effects: Vec<ObstacleEffect>) -> Self {
+        Obstacle {
+            kind: kind.to_string(),
+            height,
+            width,
+            effects,
+        }
+    }
+
+    pub fn has_effect(&self, effect: ObstacleEffect) -> bool {
+        self.effects.contains(&effect)
+    }
+
+    /// How fast this obstacle pushes characters away, if it does at all
+    pub fn knockback(&self) -> Option<f32> {
+        self.effects.iter().find_map(|effect| match effect {
+            ObstacleEffect::Knockback(speed) => Some(*speed),
+            _ => None,
+        })
+    }
+
+    /// How long this obstacle speeds up the player who picks it up, if it
+    /// can be picked up at all
+    pub fn boost(&self) -> Option<f32> {
+        self.effects.iter().find_map(|effect| match effect {
+            ObstacleEffect::Boost(seconds) => Some(*seconds),
+            _ => None,
+        })
+    }
+
+    /// Checks whether the given point is inside this obstacle's hitbox,
+    /// where `x` and `y` are measured from the centre of the obstacle
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        x >= -(self.width) && x <= self.width && y >= -(self.height) && y <= self.height
+    }
+
+    /// How far the given point is from this obstacle's hitbox, or 0 if it
+    /// is inside it, where `x` and `y` are measured from the centre of the
+    /// obstacle
+    pub fn distance_outside(&self, x: f32, y: f32) -> f32 {
+        let dx = (x.abs() - self.width).max(0.0);
+        let dy = (y.abs() - self.height).max(0.0);
+        dx.hypot(dy)
+    }
+
+    /// The points just outside each side of this obstacle's hitbox, `margin`
+    /// away from it, nearest to the given point first. Like the point, they
+    /// are measured from the centre of the obstacle.
+    pub fn exits(&self, x: f32, y: f32, margin: f32) -> Vec<(f32, f32)> {
+        let x_inside = x.max(-self.width).min(self.width);
+        let y_inside = y.max(-self.height).min(self.height);
+        let mut exits = vec![
+            (-(self.width + margin), y_inside),
+            (self.width + margin, y_inside),
+            (x_inside, -(self.height + margin)),
+            (x_inside, self.height + margin),
+        ];
+        // a point that isn't a number is treated as infinitely far from every
+        // exit, so that sorting can't panic on it
+        let distance = |&(exit_x, exit_y): &(f32, f32)| {
+            let distance = (exit_x - x).hypot(exit_y - y);
+            if distance.is_nan() {
+                f32::INFINITY
+            } else {
+                distance
+            }
+        };
+        exits.sort_by(|a, b| distance(a).partial_cmp(&distance(b)).unwrap());
+        exits
+    }
+
+    /// How far along a ray from `start` heading `direction` it enters this
+    /// obstacle's hitbox grown by `margin`, if it does within `reach`.
+    /// `start` is measured from the centre of the obstacle, and is 0 along
+    /// if it is already inside.
+    pub fn ray_hit(
+        &self,
+        start: Vector3<f32>,
+        direction: &Vector3<f32>,
+        margin: f32,
+        reach: f32,
+    ) -> Option<f32> {
+        let mut near = 0.0f32;
+        let mut far = reach;
+        for &(start, direction, half_size) in &[
+            (start.x, direction.x, self.width + margin),
+            (start.y, direction.y, self.height + margin),
+        ] {
+            if direction == 0.0 {
+                if start.abs() > half_size {
+                    return None;
+                }
+                continue;
+            }
+            let a = (-half_size - start) / direction;
+            let b = (half_size - start) / direction;
+            near = near.max(a.min(b));
+            far = far.min(a.max(b));
+            if near > far {
+                return None;
+            }
+        }
+        Some(near)
+    }
+
+    // deactivates obstacle's hitbox
+    pub fn deactivate_hitbox(&mut self) {
+        self.height = 0f32;
+        self.width = 0f32;
+    }
+}
+
+impl Component for Obstacle {
+    type Storage = DenseVecStorage<Self>;
+}
@@ -1,3 +1,4 @@
+use super::StatusEffects;
 use amethyst::{
     assets::PrefabData,
     derive::PrefabData,
@@ -6,41 +7,161 @@ use amethyst::{
 };
 use serde::{Deserialize, Serialize};
 
+/// How long a jump keeps the player in the air, in seconds
+const JUMP_DURATION: f32 = 0.5;
+/// How long after jumping the player has to wait to jump again, in seconds
+const JUMP_COOLDOWN: f32 = 1.2;
+/// How long the player can hide in a parked car before having to get out,
+/// in seconds
+const HIDE_DURATION: f32 = 3.0;
+/// How long after getting out of a car the player has to wait to hide
+/// again, in seconds
+const HIDE_COOLDOWN: f32 = 2.0;
+/// How many things the player has to throw at the start of a run
+const STARTING_AMMO: u32 = 3;
+/// How long after throwing the player has to wait to throw again, in seconds
+const THROW_COOLDOWN: f32 = 0.4;
+
+fn starting_ammo() -> u32 {
+    STARTING_AMMO
+}
+
 #[derive(Copy, Clone, Debug, Deserialize, PrefabData, Serialize)]
 #[prefab(Component)]
 pub struct Player {
+    /// Movement speed before any status effects, in units per second
     pub speed: f32,
-    pub is_in_car: bool,
+    /// How much the player's status effects multiply their speed by
+    #[serde(skip, default = "super::status::unaffected")]
+    speed_multiplier: f32,
+    /// How much longer the player stays in the air, in seconds
+    #[serde(default)]
+    airborne: f32,
+    /// How much longer until the player can jump again, in seconds
+    #[serde(default)]
+    jump_cooldown: f32,
+    /// The parked car the player is hiding in, if they are
+    #[serde(skip)]
+    hiding_in: Option<Entity>,
+    /// How much longer the player can stay hidden, in seconds
+    #[serde(default)]
+    hide_remaining: f32,
+    /// How much longer until the player can hide again, in seconds
+    #[serde(default)]
+    hide_cooldown: f32,
+    /// How many things the player has left to throw
+    #[serde(default = "starting_ammo")]
+    ammo: u32,
+    /// How much longer until the player can throw again, in seconds
+    #[serde(default)]
+    throw_cooldown: f32,
 }
 
 impl Player {
-    pub fn shoot(&self) {
-        println!("PEW!");
+    /// Movement speed after status effects, in units per second
+    pub fn effective_speed(&self) -> f32 {
+        self.speed * self.speed_multiplier
+    }
+
+    /// Resolve the player's status effects into their speed
+    pub fn apply_effects(&mut self, effects: &StatusEffects) {
+        self.speed_multiplier = effects.speed_multiplier();
+    }
+
+    /// Jump into the air, unless the last jump is still cooling down.
+    /// Returns whether the player jumped.
+    pub fn jump(&mut self) -> bool {
+        if self.jump_cooldown > 0.0 {
+            return false;
+        }
+        self.airborne = JUMP_DURATION;
+        self.jump_cooldown = JUMP_COOLDOWN;
+        true
+    }
+
+    /// Whether the player is in the air, clearing mud and dogs
+    pub fn is_airborne(&self) -> bool {
+        self.airborne > 0.0
+    }
+
+    /// Count down the jump timers by `seconds`
+    pub fn tick_jump(&mut self, seconds: f32) {
+        self.airborne = (self.airborne - seconds).max(0.0);
+        self.jump_cooldown = (self.jump_cooldown - seconds).max(0.0);
     }
-    //slows player down
-    pub fn slow_down(&mut self) {
-        self.speed = 5.0f32;
+
+    /// Climb into `car` to hide from the enemy, unless the player only just
+    /// got out of one. Returns whether the player hid.
+    pub fn hide_in(&mut self, car: Entity) -> bool {
+        if self.hiding_in.is_some() || self.hide_cooldown > 0.0 {
+            return false;
+        }
+        self.hiding_in = Some(car);
+        self.hide_remaining = HIDE_DURATION;
+        true
+    }
+
+    /// The parked car the player is hiding in, if they are
+    pub fn hiding_in(&self) -> Option<Entity> {
+        self.hiding_in
+    }
+
+    /// Whether the player is hidden in a car, out of the enemy's sight
+    pub fn is_hidden(&self) -> bool {
+        self.hiding_in.is_some()
     }
 
-    //returns speed back to normal
-    pub fn normal_speed(&mut self) {
-        self.speed = 10.0f32;
+    /// Get out of the car the player is hiding in, returning it
+    pub fn leave_car(&mut self) -> Option<Entity> {
+        let car = self.hiding_in.take()?;
+        self.hide_remaining = 0.0;
+        self.hide_cooldown = HIDE_COOLDOWN;
+        Some(car)
+    }
+
+    /// Count down the hiding timers by `seconds`, returning whether the
+    /// player has run out of time to hide and has to get out
+    pub fn tick_hiding(&mut self, seconds: f32) -> bool {
+        self.hide_cooldown = (self.hide_cooldown - seconds).max(0.0);
+        if self.hiding_in.is_none() {
+            return false;
+        }
+        self.hide_remaining = (self.hide_remaining - seconds).max(0.0);
+        self.hide_remaining == 0.0
+    }
+
+    /// Throw something, unless the player has nothing left to throw or
+    /// only just threw. Returns whether the player threw.
+    pub fn throw(&mut self) -> bool {
+        if self.ammo == 0 || self.throw_cooldown > 0.0 {
+            return false;
+        }
+        self.ammo -= 1;
+        self.throw_cooldown = THROW_COOLDOWN;
+        true
     }
 
-    //doubles speed
-    pub fn speed_up(&mut self) {
-        self.speed = 20.0f32;
+    /// How many things the player has left to throw
+    pub fn ammo(&self) -> u32 {
+        self.ammo
     }
 
-    //Stops player
-    pub fn stop(&mut self) {
-        self.speed = 0.0f32;
+    /// Count down the throw cooldown by `seconds`
+    pub fn tick_throw(&mut self, seconds: f32) {
+        self.throw_cooldown = (self.throw_cooldown - seconds).max(0.0);
     }
 
     pub fn new() -> Self {
         Player {
-            speed: 10.0f32,
-            is_in_car: false,
+            speed: 600.0f32,
+            speed_multiplier: 1.0,
+            airborne: 0.0,
+            jump_cooldown: 0.0,
+            hiding_in: None,
+            hide_remaining: 0.0,
+            hide_cooldown: 0.0,
+            ammo: STARTING_AMMO,
+            throw_cooldown: 0.0,
         }
     }
 }
@@ -0,0 +1,28 @@
+use amethyst::ecs::{Component, DenseVecStorage};
+use nalgebra::base::Vector3;
+
+/// Something the player threw, flying along until it hits someone or lands
+#[derive(Copy, Clone, Debug)]
+pub struct Projectile {
+    /// How fast and which way it flies, in units per second
+    pub velocity: Vector3<f32>,
+    /// How much longer it flies before landing, in seconds
+    lifetime: f32,
+}
+
+impl Projectile {
+    pub fn new(velocity: Vector3<f32>, lifetime: f32) -> Self {
+        Projectile { velocity, lifetime }
+    }
+
+    /// Count down the time left in the air by `seconds`, returning whether
+    /// it is still flying
+    pub fn tick(&mut self, seconds: f32) -> bool {
+        self.lifetime -= seconds;
+        self.lifetime > 0.0
+    }
+}
+
+impl Component for Projectile {
+    type Storage = DenseVecStorage<Self>;
+}
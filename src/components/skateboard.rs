@@ -0,0 +1,10 @@
+use amethyst::ecs::{Component, NullStorage};
+
+/// The skateboard drawn under the player, which is only shown while they
+/// are riding it
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Skateboard;
+
+impl Component for Skateboard {
+    type Storage = NullStorage<Self>;
+}
@@ -0,0 +1,153 @@
+use amethyst::ecs::{Component, DenseVecStorage};
+use serde::{Deserialize, Serialize};
+
+/// How much slowing effects like mud multiply speed by
+const SLOW_MULTIPLIER: f32 = 0.5;
+/// How much a skateboard multiplies speed by
+const BOOST_MULTIPLIER: f32 = 2.0;
+/// How long a knocked out character can't be slowed or stunned again once
+/// they come round, in seconds
+const RECOVERY_SECONDS: f32 = 1.0;
+
+/// The speed multiplier of a character with no effects on it
+pub(super) fn unaffected() -> f32 {
+    1.0
+}
+
+/// The kinds of effect which can be applied to a character
+#[derive(Copy, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum StatusKind {
+    /// Slows the character down
+    Slow,
+    /// Stops the character in place
+    Stun,
+    /// Speeds the character up
+    Boost,
+    /// Keeps slows and stuns from being applied
+    Invulnerable,
+}
+
+/// A timed effect on a character
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct StatusEffect {
+    pub kind: StatusKind,
+    /// How much the effect multiplies the character's speed by
+    pub multiplier: f32,
+    /// How much longer the effect lasts, in seconds
+    pub remaining: f32,
+}
+
+impl StatusEffect {
+    pub fn slow(seconds: f32) -> Self {
+        StatusEffect {
+            kind: StatusKind::Slow,
+            multiplier: SLOW_MULTIPLIER,
+            remaining: seconds,
+        }
+    }
+
+    pub fn stun(seconds: f32) -> Self {
+        StatusEffect {
+            kind: StatusKind::Stun,
+            multiplier: 0.0,
+            remaining: seconds,
+        }
+    }
+
+    pub fn boost(seconds: f32) -> Self {
+        StatusEffect {
+            kind: StatusKind::Boost,
+            multiplier: BOOST_MULTIPLIER,
+            remaining: seconds,
+        }
+    }
+
+    pub fn invulnerable(seconds: f32) -> Self {
+        StatusEffect {
+            kind: StatusKind::Invulnerable,
+            multiplier: 1.0,
+            remaining: seconds,
+        }
+    }
+
+    /// Whether this effect hinders the character it is applied to
+    pub fn is_harmful(&self) -> bool {
+        match self.kind {
+            StatusKind::Slow | StatusKind::Stun => true,
+            StatusKind::Boost | StatusKind::Invulnerable => false,
+        }
+    }
+}
+
+/// Every effect currently on a character. Different effects stack, so a
+/// slow and a boost multiply together, while applying an effect of the same
+/// kind and strength again only refreshes it, keeping whichever time left
+/// is longer.
+#[derive(Clone, Debug, Default)]
+pub struct StatusEffects {
+    effects: Vec<StatusEffect>,
+}
+
+impl StatusEffects {
+    /// Add an effect, returning whether it took hold. Harmful effects don't
+    /// take hold on invulnerable characters.
+    pub fn apply(&mut self, effect: StatusEffect) -> bool {
+        if effect.is_harmful() && self.has(StatusKind::Invulnerable) {
+            return false;
+        }
+        match self
+            .effects
+            .iter_mut()
+            .find(|other| other.kind == effect.kind && other.multiplier == effect.multiplier)
+        {
+            Some(other) => other.remaining = other.remaining.max(effect.remaining),
+            None => self.effects.push(effect),
+        }
+        true
+    }
+
+    /// Stun the character for `seconds`, then keep them from being slowed
+    /// or stunned again until they have had a moment to recover, so that
+    /// they can't be knocked straight back out. Returns whether the stun
+    /// took hold.
+    pub fn knock_out(&mut self, seconds: f32) -> bool {
+        let stunned = self.apply(StatusEffect::stun(seconds));
+        if stunned {
+            self.apply(StatusEffect::invulnerable(seconds + RECOVERY_SECONDS));
+        }
+        stunned
+    }
+
+    pub fn has(&self, kind: StatusKind) -> bool {
+        self.effects.iter().any(|effect| effect.kind == kind)
+    }
+
+    /// End every effect of the given kind early
+    pub fn remove(&mut self, kind: StatusKind) {
+        self.effects.retain(|effect| effect.kind != kind);
+    }
+
+    /// Count the effects down by `seconds`, ending the ones which run out
+    pub fn tick(&mut self, seconds: f32) {
+        for effect in &mut self.effects {
+            effect.remaining -= seconds;
+        }
+        self.effects.retain(|effect| effect.remaining > 0.0);
+    }
+
+    /// How much the effects multiply the character's speed by, all together
+    pub fn speed_multiplier(&self) -> f32 {
+        self.effects
+            .iter()
+            .map(|effect| effect.multiplier)
+            .product()
+    }
+
+    pub fn effects(&self) -> &[StatusEffect] {
+        &self.effects
+    }
+}
+
+impl Component for StatusEffects {
+    type Storage = DenseVecStorage<Self>;
+}
@@ -0,0 +1,105 @@
+use super::{Obstacle, ObstacleEffect};
+use amethyst::{
+    assets::PrefabData,
+    derive::PrefabData,
+    ecs::{Component, DenseVecStorage, Entity, WriteStorage},
+    Error,
+};
+use nalgebra::base::Vector3;
+use serde::{Deserialize, Serialize};
+
+/// How strongly a character steers around obstacles with a given effect
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Avoidance {
+    pub effect: ObstacleEffect,
+    pub weight: f32,
+}
+
+/// Steers a character towards where it is going while looking ahead for
+/// obstacles to go around, instead of walking straight through them
+#[derive(Clone, Debug, Deserialize, PrefabData, Serialize)]
+#[prefab(Component)]
+#[serde(deny_unknown_fields)]
+pub struct Steering {
+    /// How strongly the character heads straight for where it is going
+    pub seek_weight: f32,
+    /// How far ahead the character looks for obstacles, in units
+    pub look_ahead: f32,
+    /// How much room the character leaves around an obstacle, in units
+    pub clearance: f32,
+    /// Which obstacles to steer around. Obstacles without any of these
+    /// effects get walked straight through.
+    pub avoid: Vec<Avoidance>,
+}
+
+impl Steering {
+    /// How strongly to steer around `obstacle`, going by the strongest of
+    /// its effects
+    fn avoidance_weight(&self, obstacle: &Obstacle) -> f32 {
+        self.avoid
+            .iter()
+            .filter(|avoidance| obstacle.has_effect(avoidance.effect))
+            .map(|avoidance| avoidance.weight)
+            .fold(0.0, f32::max)
+    }
+
+    /// Which way to head from `position` to reach `target`, as a unit
+    /// vector, steering around the nearest of `obstacles` in the way. Each
+    /// obstacle comes with where its centre is.
+    pub fn steer(
+        &self,
+        position: &Vector3<f32>,
+        target: &Vector3<f32>,
+        obstacles: &[(&Obstacle, Vector3<f32>)],
+    ) -> Vector3<f32> {
+        let mut seek = target - position;
+        seek.z = 0.0;
+        let distance = seek.norm();
+        if distance == 0.0 {
+            return seek;
+        }
+        let seek = seek / distance;
+        // don't look past the target, so obstacles behind it don't matter
+        let reach = self.look_ahead.min(distance);
+
+        // finds the nearest obstacle the character would walk into
+        let threat = obstacles
+            .iter()
+            .filter_map(|(obstacle, centre)| {
+                let weight = self.avoidance_weight(obstacle);
+                if weight <= 0.0 {
+                    return None;
+                }
+                let along = obstacle.ray_hit(position - centre, &seek, self.clearance, reach)?;
+                Some((along, weight, centre))
+            })
+            // a bad position can give distances which aren't numbers
+            .filter(|(along, ..)| along.is_finite())
+            .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+        let mut heading = seek * self.seek_weight;
+        if let Some((along, weight, centre)) = threat {
+            // pushes sideways away from the obstacle's centre, harder the
+            // closer it is
+            let to_centre = centre - position;
+            let mut sideways = seek * to_centre.dot(&seek) - to_centre;
+            sideways.z = 0.0;
+            if sideways.norm_squared() == 0.0 {
+                // heading straight at the middle, so pick a side
+                sideways = Vector3::new(-seek.y, seek.x, 0.0);
+            }
+            let urgency = 1.0 - along / self.look_ahead.max(f32::EPSILON);
+            heading += sideways.normalize() * weight * urgency;
+        }
+        if heading.norm_squared() == 0.0 {
+            seek
+        } else {
+            heading.normalize()
+        }
+    }
+}
+
+impl Component for Steering {
+    type Storage = DenseVecStorage<Self>;
+}
@@ -0,0 +1,100 @@
+use super::{Facing, Obstacle};
+use amethyst::{
+    assets::PrefabData,
+    derive::PrefabData,
+    ecs::{Component, DenseVecStorage, Entity, WriteStorage},
+    Error,
+};
+use serde::{Deserialize, Serialize};
+
+/// A road across the street, which sends cars driving along it one after
+/// the other
+#[derive(Clone, Debug, Deserialize, PrefabData, Serialize)]
+#[prefab(Component)]
+#[serde(deny_unknown_fields)]
+pub struct TrafficLane {
+    /// The name of the obstacle prefab the cars are made from
+    pub car: String,
+    /// How fast the cars drive, in units per second
+    pub speed: f32,
+    /// Seconds between one car setting off and the next
+    pub interval: f32,
+    /// Which way the cars drive. Each lane picks a way at random when this
+    /// is left out.
+    #[serde(default)]
+    pub direction: Option<Facing>,
+    /// Seconds until the next car sets off, once the lane has started
+    #[serde(skip)]
+    until_next_car: Option<f32>,
+}
+
+impl TrafficLane {
+    pub fn is_started(&self) -> bool {
+        self.until_next_car.is_some()
+    }
+
+    /// Start sending cars along the lane, the first one after `delay`
+    /// seconds. `direction` is only used if the lane doesn't have one.
+    pub fn start(&mut self, direction: Facing, delay: f32) {
+        self.direction = self.direction.or(Some(direction));
+        self.until_next_car = Some(delay);
+    }
+
+    /// Which way the cars drive
+    pub fn direction(&self) -> Facing {
+        self.direction.unwrap_or_default()
+    }
+
+    /// Count down to the next car by `seconds`, returning whether it sets
+    /// off now
+    pub fn tick(&mut self, seconds: f32) -> bool {
+        match &mut self.until_next_car {
+            Some(remaining) => {
+                *remaining -= seconds;
+                if *remaining <= 0.0 {
+                    *remaining += self.interval;
+                    true
+                } else {
+                    false
+                }
+            }
+            None => false,
+        }
+    }
+}
+
+impl Component for TrafficLane {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// A car driving along a traffic lane the way it is facing. Its hitbox
+/// lives on its `Obstacle`.
+#[derive(Copy, Clone, Debug)]
+pub struct TrafficCar {
+    /// Driving speed, in units per second
+    pub speed: f32,
+    wrecked: bool,
+}
+
+impl TrafficCar {
+    pub fn new(speed: f32) -> Self {
+        TrafficCar {
+            speed,
+            wrecked: false,
+        }
+    }
+
+    pub fn is_wrecked(&self) -> bool {
+        self.wrecked
+    }
+
+    /// Stop the car for good, leaving a wreck nobody can bump into
+    pub fn wreck(&mut self, obstacle: &mut Obstacle) {
+        self.wrecked = true;
+        obstacle.deactivate_hitbox();
+    }
+}
+
+impl Component for TrafficCar {
+    type Storage = DenseVecStorage<Self>;
+}
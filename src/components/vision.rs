@@ -0,0 +1,68 @@
+use super::{Obstacle, ObstacleEffect};
+use amethyst::{
+    assets::PrefabData,
+    derive::PrefabData,
+    ecs::{Component, DenseVecStorage, Entity, WriteStorage},
+    Error,
+};
+use nalgebra::base::Vector3;
+use serde::{Deserialize, Serialize};
+
+/// What a character can see: anything in a cone ahead of it out to a
+/// distance, or anything right next to it, as long as no blocking obstacle
+/// like a car is in the way
+#[derive(Copy, Clone, Debug, Deserialize, PrefabData, Serialize)]
+#[prefab(Component)]
+#[serde(deny_unknown_fields)]
+pub struct Vision {
+    /// How far the character can see, in units
+    pub radius: f32,
+    /// How far either side of straight ahead the character can see, in
+    /// degrees
+    pub half_angle: f32,
+    /// How close something has to be for the character to notice it
+    /// whichever way it is looking, in units
+    pub close_radius: f32,
+}
+
+impl Vision {
+    /// Whether a character at `position` looking along `heading` can see
+    /// `target`. A character which hasn't looked anywhere yet sees all
+    /// around it. Each obstacle comes with where its centre is.
+    pub fn can_see(
+        &self,
+        position: &Vector3<f32>,
+        heading: &Vector3<f32>,
+        target: &Vector3<f32>,
+        obstacles: &[(&Obstacle, Vector3<f32>)],
+    ) -> bool {
+        let mut offset = target - position;
+        offset.z = 0.0;
+        let distance = offset.norm();
+        if distance > self.radius {
+            return false;
+        }
+        if distance == 0.0 {
+            return true;
+        }
+        let direction = offset / distance;
+        if distance > self.close_radius && heading.norm_squared() != 0.0 {
+            let cosine = direction.dot(&heading.normalize());
+            if cosine < self.half_angle.to_radians().cos() {
+                return false;
+            }
+        }
+        // blocking obstacles like cars can't be seen through, though one
+        // the character is standing in or against doesn't block its view
+        !obstacles.iter().any(|(obstacle, centre)| {
+            let start = position - centre;
+            obstacle.has_effect(ObstacleEffect::Block)
+                && !obstacle.contains(start.x, start.y)
+                && obstacle.ray_hit(start, &direction, 0.0, distance).is_some()
+        })
+    }
+}
+
+impl Component for Vision {
+    type Storage = DenseVecStorage<Self>;
+}
@@ -0,0 +1,9 @@
+//! The game's logic, shared between the game binary and the headless
+//! simulation harness
+
+pub mod components;
+pub mod resources;
+pub mod simulation;
+pub mod states;
+pub mod systems;
+pub mod utils;
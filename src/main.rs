@@ -4,6 +4,7 @@
 use amethyst::{
     assets::PrefabLoaderSystemDesc,
     audio::{AudioBundle, DjSystem},
+    config::Config,
     core::transform::TransformBundle,
     input::{InputBundle, StringBindings},
     prelude::*,
@@ -14,13 +15,12 @@ use amethyst::{
     },
     ui::{RenderUi, UiBundle},
     utils::application_root_dir,
+    window::DisplayConfig,
+};
+use game::{
+    resources::{self, GameplayConfig, Settings},
+    states, systems,
 };
-
-pub mod components;
-pub mod resources;
-pub mod states;
-pub mod systems;
-pub mod utils;
 
 fn main() -> amethyst::Result<()> {
     amethyst::start_logger(Default::default());
@@ -31,6 +31,17 @@ fn main() -> amethyst::Result<()> {
     let config_dir = app_root.join("config");
     let display_config_path = config_dir.join("display.ron");
 
+    // the player's own settings take priority over the config files
+    let settings = Settings::load_or_default();
+    let mut display_config = DisplayConfig::load(display_config_path)?;
+    settings.display.apply(&mut display_config);
+
+    // the level seed can be fixed in the config, or on the command line
+    let mut gameplay_config = GameplayConfig::load(config_dir.join("gameplay.ron"))?;
+    if let Some(seed) = seed_from_args() {
+        gameplay_config.seed = Some(seed);
+    }
+
     // adds key bindings & creates input handle
     let bindings_config = config_dir.join("inputs.ron");
     let input_bundle =
@@ -42,7 +53,7 @@ fn main() -> amethyst::Result<()> {
         .with_bundle(UiBundle::<StringBindings>::new())?
         .with_bundle(AudioBundle::default())?
         .with(
-            DjSystem::new(|music: &mut resources::audio::Music| music.music.next()),
+            DjSystem::new(|music: &mut resources::audio::Music| music.next()),
             "dj",
             &[],
         )
@@ -50,81 +61,55 @@ fn main() -> amethyst::Result<()> {
         .with_bundle(
             RenderingBundle::<DefaultBackend>::new()
                 .with_plugin(
-                    RenderToWindow::from_config_path(display_config_path)?
-                        .with_clear([0.34, 0.36, 0.52, 1.0]),
+                    RenderToWindow::from_config(display_config).with_clear([0.34, 0.36, 0.52, 1.0]),
                 )
                 .with_plugin(RenderUi::default())
                 .with_plugin(RenderFlat2D::default()),
-        )?
-        .with_system_desc(
-            PrefabLoaderSystemDesc::<resources::prefabs::CharacterPrefab>::default(),
-            "character_prefab_loader",
-            &[],
-        )
+        )?;
+    // systems which only matter when the game is drawn to the screen and heard
+    let game_data = systems::with_gameplay_systems(game_data)
         .with_system_desc(
             PrefabLoaderSystemDesc::<resources::prefabs::BackgroundPrefab>::default(),
             "background_prefab_loader",
             &[],
         )
-        .with_system_desc(
-            PrefabLoaderSystemDesc::<resources::prefabs::ObstaclePrefab>::default(),
-            "obstacle_prefab_loader",
-            &[],
-        )
-        .with(systems::PlayerSystem, "player_system", &["input_system"])
-        .with(
-            systems::PlayerCollisionSystem,
-            "player_col_system",
-            &["player_system"],
-        )
-        .with(
-            systems::ScoreTrackingSystem,
-            "score_system",
-            &["player_system"],
-        )
         .with(
             systems::BackgroundRepeatSystem,
             "background_repeat_system",
             &["player_system"],
         )
-        .with(
-            systems::ObstacleRandomizationSystem,
-            "obstacle_randomization_system",
-            &["player_system"],
-        )
-        .with(
-            systems::EnemyMovementSystem,
-            "enemy_movement_system",
-            &["player_system"],
-        )
-        .with(
-            systems::EnemyObjectCollisionSystem,
-            "enemy_object_collision_system",
-            &["player_system"],
-        )
-        .with(
-            systems::EnemyCollisionSystem,
-            "enemy_collision_system",
-            &["player_system", "enemy_movement_system"],
-        )
-        .with(
-            systems::DogCollisionSystem,
-            "dog_collision_system",
-            &["player_system"],
+        .with_system_desc(
+            systems::SoundEffectSystemDesc::default(),
+            "sound_effect_system",
+            &["obstacle_contact_system", "enemy_collision_system"],
         )
-        .with(systems::CarSystem, "car_system", &["player_system"])
-        /*
+        .with_system_desc(systems::MuteSystemDesc::default(), "mute_system", &[])
         .with(
-            systems::DogAttackSystem,
-            "dog_attack_system",
-            &["player_system"],
-        )
-        */
-        .with(systems::MudSystem, "mud_system", &["player_system"])
-        .with(systems::DogSystem, "dog_system", &["player_system"]);
+            systems::AdaptiveMusicSystem::default(),
+            "adaptive_music_system",
+            &["enemy_movement_system", "mute_system"],
+        );
 
-    let mut game = Application::new(assets_dir, states::LoadingState::default(), game_data)?;
+    let mut game = Application::build(assets_dir, states::LoadingState::default())?
+        .with_resource(gameplay_config)
+        .with_resource(settings)
+        .build(game_data)?;
     game.run();
 
     Ok(())
 }
+
+/// Read the level seed from a `--seed <number>` argument, if one was given
+fn seed_from_args() -> Option<u64> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--seed" {
+            let seed = args.next().and_then(|seed| seed.parse().ok());
+            if seed.is_none() {
+                log::warn!("--seed must be followed by a whole number");
+            }
+            return seed;
+        }
+    }
+    None
+}
@@ -1,41 +1,367 @@
 use amethyst::{
-    assets::{Loader, ProgressCounter},
-    audio::{output::init_output, AudioSink, SourceHandle, WavFormat},
+    assets::{AssetStorage, Loader, ProgressCounter},
+    audio::{
+        output::{init_output, Output},
+        AudioSink, FlacFormat, Mp3Format, OggFormat, Source, SourceHandle, WavFormat,
+    },
+    config::Config,
     prelude::*,
+    utils::application_root_dir,
 };
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, path::Path};
 
-use std::{iter::Cycle, vec::IntoIter};
+/// Lists the tracks in each playlist, relative to the assets directory
+const PLAYLIST_MANIFEST: &str = "audio/playlists.ron";
 
-const SOUNDTRACK: &[&str] = &["audio/BeepBox-Song.wav"];
+/// The playlists each part of the game plays
+pub const MENU_PLAYLIST: &str = "menu";
+pub const CUTSCENE_PLAYLIST: &str = "cutscene";
+pub const GAMEPLAY_PLAYLIST: &str = "gameplay";
 
+/// The name of the sound played when the enemy catches the player
+pub const CAUGHT_SOUND: &str = "caught";
+
+/// Sound effects by name. Touching an obstacle plays the sound named
+/// after its kind.
+const SOUND_EFFECTS: &[(&str, &str)] = &[
+    ("mud", "audio/sfx/splash.wav"),
+    ("car", "audio/sfx/thud.wav"),
+    ("police", "audio/sfx/thud.wav"),
+    ("dog", "audio/sfx/bark.wav"),
+    (CAUGHT_SOUND, "audio/sfx/caught.wav"),
+];
+
+/// The order a playlist's tracks are played in
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum PlaybackMode {
+    /// Every track in order, then start again
+    RepeatAll,
+    /// The first track over and over
+    RepeatOne,
+    /// Every track in a random order, reshuffled each time through
+    Shuffle,
+}
+impl Default for PlaybackMode {
+    fn default() -> Self {
+        PlaybackMode::RepeatAll
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct PlaylistManifest {
+    playlists: HashMap<String, PlaylistEntry>,
+    #[serde(default)]
+    chase: Option<ChaseEntry>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+struct PlaylistEntry {
+    tracks: Vec<String>,
+    mode: PlaybackMode,
+}
+
+struct Playlist {
+    tracks: Vec<SourceHandle>,
+    mode: PlaybackMode,
+}
+
+/// Stems layered over the gameplay playlist while the enemy gives chase
+#[derive(Debug, Deserialize, Serialize)]
+struct ChaseEntry {
+    calm: String,
+    tense: String,
+    critical: String,
+    /// Closer than this, the tense stem plays
+    tense_distance: f32,
+    /// Closer than this, the critical stem plays
+    critical_distance: f32,
+}
+
+/// How close the enemy is to catching the player
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Intensity {
+    Calm,
+    Tense,
+    Critical,
+}
+
+/// One stem of the chase music, playing on its own sink so it can be
+/// faded independently of the others
+pub struct ChaseLayer {
+    pub intensity: Intensity,
+    pub track: SourceHandle,
+    pub sink: Option<AudioSink>,
+    /// How loud this layer is relative to the music volume, from 0 to 1
+    pub mix: f32,
+}
+
+/// The chase stems, which `AdaptiveMusicSystem` crossfades between
+pub struct ChaseMusic {
+    pub layers: Vec<ChaseLayer>,
+    tense_distance: f32,
+    critical_distance: f32,
+    /// How loud the playlist is relative to the music volume, from 0 to 1
+    pub playlist_mix: f32,
+}
+impl Default for ChaseMusic {
+    fn default() -> Self {
+        ChaseMusic {
+            layers: Vec::new(),
+            tense_distance: 0.0,
+            critical_distance: 0.0,
+            playlist_mix: 1.0,
+        }
+    }
+}
+impl ChaseMusic {
+    /// The intensity the music should have with the enemy this far away
+    pub fn intensity(&self, distance: f32) -> Intensity {
+        if distance < self.critical_distance {
+            Intensity::Critical
+        } else if distance < self.tense_distance {
+            Intensity::Tense
+        } else {
+            Intensity::Calm
+        }
+    }
+}
+
+/// The soundtrack, which `DjSystem` plays from
+#[derive(Default)]
 pub struct Music {
-    pub music: Cycle<IntoIter<SourceHandle>>,
+    playlists: HashMap<String, Playlist>,
+    current: Option<String>,
+    /// The rest of the current playlist, in reverse order
+    queue: Vec<SourceHandle>,
 }
+impl Music {
+    /// The next track to play, if a playlist is playing
+    pub fn next(&mut self) -> Option<SourceHandle> {
+        if self.queue.is_empty() {
+            let playlist = self.playlists.get(self.current.as_ref()?)?;
+            self.queue = match playlist.mode {
+                PlaybackMode::RepeatAll => playlist.tracks.iter().rev().cloned().collect(),
+                PlaybackMode::RepeatOne => playlist.tracks.iter().take(1).cloned().collect(),
+                PlaybackMode::Shuffle => {
+                    let mut tracks = playlist.tracks.clone();
+                    tracks.shuffle(&mut rand::thread_rng());
+                    tracks
+                }
+            };
+        }
+        self.queue.pop()
+    }
 
+    /// Start playing from the named playlist, returning whether it wasn't
+    /// already playing
+    fn switch_to(&mut self, name: &str) -> bool {
+        if self.current.as_deref() == Some(name) {
+            return false;
+        }
+        if !self.playlists.contains_key(name) {
+            log::warn!("Tried to play non-existant playlist {}", name);
+        }
+        self.current = Some(name.to_string());
+        self.queue.clear();
+        true
+    }
+}
+
+/// Switch the music to the named playlist, cutting off the current track
+/// unless that playlist is already playing
+pub fn play_playlist(world: &mut World, name: &str) {
+    let switched = world
+        .try_fetch_mut::<Music>()
+        .map_or(false, |mut music| music.switch_to(name));
+    if !switched {
+        return;
+    }
+    // a stopped sink can't be restarted, so the old one is replaced
+    let sink = world.try_fetch::<Output>().map(|output| {
+        let mut sink = AudioSink::new(&output);
+        if let Some(old_sink) = world.try_fetch::<AudioSink>() {
+            sink.set_volume(old_sink.volume());
+            old_sink.stop();
+        }
+        sink
+    });
+    if let Some(sink) = sink {
+        world.insert(sink);
+    }
+}
+
+#[derive(Default)]
+pub struct SoundEffectRegistry {
+    sounds: HashMap<String, SourceHandle>,
+}
+impl super::ResourceRegistry for SoundEffectRegistry {
+    type ResourceType = SourceHandle;
+
+    fn find(&self, _: &World, name: &str) -> Option<Self::ResourceType> {
+        self.find_sans_world(name)
+    }
+}
+impl SoundEffectRegistry {
+    pub fn find_sans_world(&self, name: &str) -> Option<SourceHandle> {
+        self.sounds.get(name).cloned()
+    }
+}
+
+/// Load a track in whichever format its extension says it is in
 fn load_audio_song(
     loader: &Loader,
-    world: &World,
+    storage: &AssetStorage<Source>,
     file: &str,
     counter: &mut ProgressCounter,
-) -> SourceHandle {
-    loader.load(file, WavFormat, counter, &world.read_resource())
+) -> Option<SourceHandle> {
+    let extension = Path::new(file)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_lowercase);
+    match extension.as_deref() {
+        Some("wav") => Some(loader.load(file, WavFormat, &mut *counter, storage)),
+        Some("ogg") => Some(loader.load(file, OggFormat, &mut *counter, storage)),
+        Some("flac") => Some(loader.load(file, FlacFormat, &mut *counter, storage)),
+        Some("mp3") => Some(loader.load(file, Mp3Format, &mut *counter, storage)),
+        _ => None,
+    }
+}
+
+/// Expand a playlist entry into the music files it names, so that a
+/// directory stands for every music file inside it
+fn expand_track(assets_dir: &Path, track: &str) -> Vec<String> {
+    let path = assets_dir.join(track);
+    if !path.is_dir() {
+        return vec![track.to_string()];
+    }
+    let mut files = std::fs::read_dir(&path)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| {
+                    let name = entry.ok()?.file_name();
+                    Some(format!(
+                        "{}/{}",
+                        track.trim_end_matches('/'),
+                        name.to_str()?
+                    ))
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_else(|e| {
+            log::warn!("Couldn't read music directory {}: {}", track, e);
+            Vec::new()
+        });
+    files.sort();
+    files
+}
+
+fn load_music(world: &World, counter: &mut ProgressCounter) -> (Music, ChaseMusic) {
+    let assets_dir = application_root_dir().unwrap().join("assets");
+    let manifest = PlaylistManifest::load(assets_dir.join(PLAYLIST_MANIFEST)).unwrap_or_else(|e| {
+        log::warn!("Couldn't load playlists, so there will be no music: {}", e);
+        PlaylistManifest::default()
+    });
+    let loader = world.read_resource::<Loader>();
+    let storage = world.read_resource::<AssetStorage<Source>>();
+    // tracks shared between playlists are only loaded once
+    let mut loaded: HashMap<String, SourceHandle> = HashMap::new();
+    let playlists = manifest
+        .playlists
+        .into_iter()
+        .map(|(name, entry)| {
+            let tracks = entry
+                .tracks
+                .iter()
+                .flat_map(|track| expand_track(&assets_dir, track))
+                .filter_map(|file| {
+                    if let Some(handle) = loaded.get(&file) {
+                        return Some(handle.clone());
+                    }
+                    let handle = load_audio_song(&loader, &storage, &file, counter);
+                    match &handle {
+                        Some(handle) => {
+                            loaded.insert(file, handle.clone());
+                        }
+                        None => log::warn!("Skipping {}, which isn't a music file", file),
+                    }
+                    handle
+                })
+                .collect();
+            (
+                name,
+                Playlist {
+                    tracks,
+                    mode: entry.mode,
+                },
+            )
+        })
+        .collect();
+    let chase = manifest.chase.map_or_else(ChaseMusic::default, |chase| {
+        let layers = vec![
+            (Intensity::Calm, chase.calm),
+            (Intensity::Tense, chase.tense),
+            (Intensity::Critical, chase.critical),
+        ]
+        .into_iter()
+        .filter_map(|(intensity, file)| {
+            let track = loaded
+                .get(&file)
+                .cloned()
+                .or_else(|| load_audio_song(&loader, &storage, &file, counter));
+            if track.is_none() {
+                log::warn!("Skipping chase stem {}, which isn't a music file", file);
+            }
+            Some(ChaseLayer {
+                intensity,
+                track: track?,
+                sink: None,
+                mix: 0.0,
+            })
+        })
+        .collect();
+        ChaseMusic {
+            layers,
+            tense_distance: chase.tense_distance,
+            critical_distance: chase.critical_distance,
+            playlist_mix: 1.0,
+        }
+    });
+    (
+        Music {
+            playlists,
+            current: None,
+            queue: Vec::new(),
+        },
+        chase,
+    )
 }
 
 pub fn initialize_audio(world: &mut World) -> ProgressCounter {
     let mut counter = ProgressCounter::new();
     init_output(world);
-    let music = {
+    let (music, chase_music) = load_music(world, &mut counter);
+    let sound_effects = {
         let loader = world.read_resource::<Loader>();
-        let mut sink = world.write_resource::<AudioSink>();
-        sink.set_volume(1.0);
-        let music = SOUNDTRACK
+        let storage = world.read_resource::<AssetStorage<Source>>();
+        if let Some(mut sink) = world.try_fetch_mut::<AudioSink>() {
+            sink.set_volume(1.0);
+        }
+        let sounds = SOUND_EFFECTS
             .iter()
-            .map(|file| load_audio_song(&loader, world, file, &mut counter))
-            .collect::<Vec<_>>()
-            .into_iter()
-            .cycle();
-        Music { music }
+            .filter_map(|(name, file)| {
+                Some((
+                    name.to_string(),
+                    load_audio_song(&loader, &storage, file, &mut counter)?,
+                ))
+            })
+            .collect();
+        SoundEffectRegistry { sounds }
     };
     world.insert(music);
+    world.insert(chase_music);
+    world.insert(sound_effects);
     counter
 }
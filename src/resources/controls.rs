@@ -1,14 +1,49 @@
 use amethyst::{
-    input::{Axis, Bindings, Button, InputHandler, StringBindings, VirtualKeyCode},
+    config::Config,
+    input::{
+        Axis, BindingError, Bindings, Button, ControllerAxis, InputHandler, StringBindings,
+        VirtualKeyCode,
+    },
     prelude::*,
+    utils::application_root_dir,
 };
+use serde::{Deserialize, Serialize};
+
+/// The action which pauses and unpauses the game
+pub const PAUSE_ACTION: &str = "pause";
+
+/// The action which presses the selected menu button
+pub const CONFIRM_ACTION: &str = "confirm";
+
+/// The action which mutes and unmutes all sound
+pub const MUTE_ACTION: &str = "mute";
+
+/// The action which makes the player jump
+pub const JUMP_ACTION: &str = "jump";
+
+/// The action which hides the player in a parked car, or gets them out
+pub const ENTER_CAR_ACTION: &str = "enter_car";
+
+/// The action which makes the player throw something
+pub const THROW_ACTION: &str = "throw";
+
+/// The controller used by the `Controller` scheme
+const CONTROLLER_ID: u32 = 0;
+
+/// How far the sticks have to move before the player does
+const STICK_DEAD_ZONE: f64 = 0.15;
 
 /// An enum containing control schemes for the game
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub enum Controls {
     Arrows,
     Wasd,
     Ijkl,
+    /// The left stick of the first controller
+    Controller,
+    /// Bindings chosen key by key in the options, which are kept in the
+    /// `Settings`
+    Custom,
 }
 impl Default for Controls {
     fn default() -> Self {
@@ -22,6 +57,8 @@ impl Controls {
             Controls::Arrows => "Controls: Arrow keys",
             Controls::Wasd => "Controls: WASD",
             Controls::Ijkl => "Controls: IJKL",
+            Controls::Controller => "Controls: Controller",
+            Controls::Custom => "Controls: Custom",
         }
     }
 
@@ -31,86 +68,302 @@ impl Controls {
         match self {
             Controls::Arrows => Controls::Wasd,
             Controls::Wasd => Controls::Ijkl,
-            Controls::Ijkl => Controls::Arrows,
+            Controls::Ijkl => Controls::Controller,
+            Controls::Controller => Controls::Arrows,
+            Controls::Custom => Controls::Arrows,
         }
     }
 
     /// Update the world's controls to use the controls specified by
-    /// this instance
+    /// this instance. Preset schemes start over from the default bindings,
+    /// so that no custom bindings are left behind to clash with them.
     pub fn set_control_scheme(&self, world: &mut World) {
-        let bindings = &mut world
-            .write_resource::<InputHandler<StringBindings>>()
-            .bindings;
-        match self {
-            Controls::Arrows => set_arrows_bindings(bindings),
-            Controls::Wasd => set_wasd_bindings(bindings),
-            Controls::Ijkl => set_ijkl_bindings(bindings),
+        let set_scheme = match self {
+            Controls::Arrows => set_arrows_bindings,
+            Controls::Wasd => set_wasd_bindings,
+            Controls::Ijkl => set_ijkl_bindings,
+            Controls::Controller => set_controller_bindings,
+            // custom bindings are applied by `Settings::apply_controls`
+            Controls::Custom => return,
+        };
+        let mut input = world.write_resource::<InputHandler<StringBindings>>();
+        let mut bindings = default_bindings().unwrap_or_else(|| input.bindings.clone());
+        match set_scheme(&mut bindings) {
+            Ok(()) => input.bindings = bindings,
+            Err(e) => log::warn!("Couldn't switch to {:?} controls: {:?}", self, e),
         }
     }
 }
 
-/// Gets the bindings for arrow keys controls
-fn set_arrows_bindings(bindings: &mut Bindings<StringBindings>) {
-    bindings
-        .insert_axis(
-            "vertical",
-            Axis::Emulated {
-                pos: Button::Key(VirtualKeyCode::Up),
-                neg: Button::Key(VirtualKeyCode::Down),
+/// The bindings in `config/inputs.ron`, if they can be read
+pub fn default_bindings() -> Option<Bindings<StringBindings>> {
+    let path = application_root_dir()
+        .ok()?
+        .join("config")
+        .join("inputs.ron");
+    Bindings::load(&path)
+        .map_err(|e| log::warn!("Error loading default bindings: {}", e))
+        .ok()
+}
+
+/// Gets the bindings for the left stick of a controller
+fn set_controller_bindings(
+    bindings: &mut Bindings<StringBindings>,
+) -> Result<(), BindingError<StringBindings>> {
+    bindings.insert_axis(
+        "vertical",
+        Axis::Controller {
+            controller_id: CONTROLLER_ID,
+            axis: ControllerAxis::LeftY,
+            // the stick reports down as positive
+            invert: true,
+            dead_zone: STICK_DEAD_ZONE,
+        },
+    )?;
+    bindings.insert_axis(
+        "horizontal",
+        Axis::Controller {
+            controller_id: CONTROLLER_ID,
+            axis: ControllerAxis::LeftX,
+            invert: false,
+            dead_zone: STICK_DEAD_ZONE,
+        },
+    )?;
+    Ok(())
+}
+
+/// Part of an input binding which holds a single key
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BindingTarget {
+    /// The key which pushes an emulated axis towards 1
+    AxisPos(&'static str),
+    /// The key which pushes an emulated axis towards -1
+    AxisNeg(&'static str),
+    /// The key which triggers an action
+    Action(&'static str),
+}
+
+/// A key the player can rebind from the options
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Rebindable {
+    /// What to call it in the options
+    pub label: &'static str,
+    pub target: BindingTarget,
+}
+
+/// Every key the player can rebind, in the order the options list them
+pub const REBINDABLE: &[Rebindable] = &[
+    Rebindable {
+        label: "Up",
+        target: BindingTarget::AxisPos("vertical"),
+    },
+    Rebindable {
+        label: "Down",
+        target: BindingTarget::AxisNeg("vertical"),
+    },
+    Rebindable {
+        label: "Left",
+        target: BindingTarget::AxisNeg("horizontal"),
+    },
+    Rebindable {
+        label: "Right",
+        target: BindingTarget::AxisPos("horizontal"),
+    },
+    Rebindable {
+        label: "Jump",
+        target: BindingTarget::Action(JUMP_ACTION),
+    },
+    Rebindable {
+        label: "Hide in car",
+        target: BindingTarget::Action(ENTER_CAR_ACTION),
+    },
+    Rebindable {
+        label: "Throw",
+        target: BindingTarget::Action(THROW_ACTION),
+    },
+];
+
+/// Keys which can't be rebound from the options, but which rebindable
+/// keys mustn't be bound to either
+const RESERVED: &[Rebindable] = &[
+    Rebindable {
+        label: "Pause",
+        target: BindingTarget::Action(PAUSE_ACTION),
+    },
+    Rebindable {
+        label: "Mute",
+        target: BindingTarget::Action(MUTE_ACTION),
+    },
+];
+
+/// Whether a combo is pressed on a controller rather than the keyboard.
+/// Rebinding an action only replaces its keyboard combos.
+fn is_controller_combo(combo: &[Button]) -> bool {
+    combo
+        .iter()
+        .any(|button| matches!(button, Button::Controller(..)))
+}
+
+impl Rebindable {
+    /// The button currently bound to this, if there is exactly one
+    pub fn button(&self, bindings: &Bindings<StringBindings>) -> Option<Button> {
+        match self.target {
+            BindingTarget::AxisPos(axis) => match bindings.axis(axis)? {
+                Axis::Emulated { pos, .. } => Some(*pos),
+                _ => None,
             },
-        )
-        .expect("error binding controls");
-    bindings
-        .insert_axis(
-            "horizontal",
-            Axis::Emulated {
-                pos: Button::Key(VirtualKeyCode::Right),
-                neg: Button::Key(VirtualKeyCode::Left),
+            BindingTarget::AxisNeg(axis) => match bindings.axis(axis)? {
+                Axis::Emulated { neg, .. } => Some(*neg),
+                _ => None,
             },
-        )
-        .expect("error binding controls");
+            BindingTarget::Action(action) => match bindings
+                .action_bindings(action)
+                .find(|combo| !is_controller_combo(combo))?
+            {
+                [button] => Some(*button),
+                _ => None,
+            },
+        }
+    }
+
+    /// The name of the button bound to this, for showing in the options
+    pub fn button_name(&self, bindings: &Bindings<StringBindings>) -> String {
+        match self.button(bindings) {
+            Some(Button::Key(key)) => format!("{:?}", key),
+            Some(button) => format!("{:?}", button),
+            None => "Unbound".to_string(),
+        }
+    }
+
+    /// Find another rebindable or reserved key which is already bound to
+    /// `button`
+    pub fn find_conflict(
+        &self,
+        bindings: &Bindings<StringBindings>,
+        button: Button,
+    ) -> Option<&'static Rebindable> {
+        REBINDABLE
+            .iter()
+            .chain(RESERVED)
+            .find(|other| *other != self && other.button(bindings) == Some(button))
+    }
+
+    /// Bind `button` to this, replacing whatever was bound before
+    pub fn bind(
+        &self,
+        bindings: &mut Bindings<StringBindings>,
+        button: Button,
+    ) -> Result<(), String> {
+        match self.target {
+            BindingTarget::AxisPos(axis) | BindingTarget::AxisNeg(axis) => {
+                let (pos, neg) = match bindings.axis(axis) {
+                    Some(Axis::Emulated { pos, neg }) => (*pos, *neg),
+                    _ => return Err(format!("{} isn't bound to keys", axis)),
+                };
+                let new_axis = if let BindingTarget::AxisPos(_) = self.target {
+                    Axis::Emulated { pos: button, neg }
+                } else {
+                    Axis::Emulated { pos, neg: button }
+                };
+                // the axis has to be removed first, or it conflicts with itself
+                let old_axis = bindings.remove_axis(axis);
+                bindings
+                    .insert_axis(axis, new_axis)
+                    .map(|_| ())
+                    .map_err(|e| {
+                        if let Some(old_axis) = old_axis {
+                            bindings
+                                .insert_axis(axis, old_axis)
+                                .expect("error restoring binding");
+                        }
+                        format!("{:?}", e)
+                    })
+            }
+            BindingTarget::Action(action) => {
+                let old_bindings = bindings
+                    .action_bindings(action)
+                    .filter(|combo| !is_controller_combo(combo))
+                    .map(|combo| combo.to_vec())
+                    .collect::<Vec<_>>();
+                for combo in &old_bindings {
+                    bindings
+                        .remove_action_binding(action, combo)
+                        .expect("error removing binding");
+                }
+                bindings
+                    .insert_action_binding(action.to_string(), vec![button])
+                    .map_err(|e| {
+                        for combo in old_bindings {
+                            bindings
+                                .insert_action_binding(action.to_string(), combo)
+                                .expect("error restoring binding");
+                        }
+                        format!("{:?}", e)
+                    })
+            }
+        }
+    }
+}
+
+/// Gets the bindings for arrow keys controls
+fn set_arrows_bindings(
+    bindings: &mut Bindings<StringBindings>,
+) -> Result<(), BindingError<StringBindings>> {
+    bindings.insert_axis(
+        "vertical",
+        Axis::Emulated {
+            pos: Button::Key(VirtualKeyCode::Up),
+            neg: Button::Key(VirtualKeyCode::Down),
+        },
+    )?;
+    bindings.insert_axis(
+        "horizontal",
+        Axis::Emulated {
+            pos: Button::Key(VirtualKeyCode::Right),
+            neg: Button::Key(VirtualKeyCode::Left),
+        },
+    )?;
+    Ok(())
 }
 
 /// Gets the bindings for wasd controls
-fn set_wasd_bindings(bindings: &mut Bindings<StringBindings>) {
-    bindings
-        .insert_axis(
-            "vertical",
-            Axis::Emulated {
-                pos: Button::Key(VirtualKeyCode::W),
-                neg: Button::Key(VirtualKeyCode::S),
-            },
-        )
-        .expect("error binding controls");
-    bindings
-        .insert_axis(
-            "horizontal",
-            Axis::Emulated {
-                pos: Button::Key(VirtualKeyCode::D),
-                neg: Button::Key(VirtualKeyCode::A),
-            },
-        )
-        .expect("error binding controls");
+fn set_wasd_bindings(
+    bindings: &mut Bindings<StringBindings>,
+) -> Result<(), BindingError<StringBindings>> {
+    bindings.insert_axis(
+        "vertical",
+        Axis::Emulated {
+            pos: Button::Key(VirtualKeyCode::W),
+            neg: Button::Key(VirtualKeyCode::S),
+        },
+    )?;
+    bindings.insert_axis(
+        "horizontal",
+        Axis::Emulated {
+            pos: Button::Key(VirtualKeyCode::D),
+            neg: Button::Key(VirtualKeyCode::A),
+        },
+    )?;
+    Ok(())
 }
 
 /// Gets the bindings for ijkl controls
-fn set_ijkl_bindings(bindings: &mut Bindings<StringBindings>) {
-    bindings
-        .insert_axis(
-            "vertical",
-            Axis::Emulated {
-                pos: Button::Key(VirtualKeyCode::I),
-                neg: Button::Key(VirtualKeyCode::K),
-            },
-        )
-        .expect("error binding controls");
-    bindings
-        .insert_axis(
-            "horizontal",
-            Axis::Emulated {
-                pos: Button::Key(VirtualKeyCode::L),
-                neg: Button::Key(VirtualKeyCode::J),
-            },
-        )
-        .expect("error binding controls");
+fn set_ijkl_bindings(
+    bindings: &mut Bindings<StringBindings>,
+) -> Result<(), BindingError<StringBindings>> {
+    bindings.insert_axis(
+        "vertical",
+        Axis::Emulated {
+            pos: Button::Key(VirtualKeyCode::I),
+            neg: Button::Key(VirtualKeyCode::K),
+        },
+    )?;
+    bindings.insert_axis(
+        "horizontal",
+        Axis::Emulated {
+            pos: Button::Key(VirtualKeyCode::L),
+            neg: Button::Key(VirtualKeyCode::J),
+        },
+    )?;
+    Ok(())
 }
@@ -1,11 +1,24 @@
 pub mod audio;
 mod controls;
 pub mod prefabs;
+mod rng;
+pub mod save;
+mod settings;
 pub mod sprites;
 
 use amethyst::{ecs::Entity, prelude::*};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
-pub use controls::Controls;
+pub use controls::{
+    default_bindings, BindingTarget, Controls, Rebindable, CONFIRM_ACTION, ENTER_CAR_ACTION,
+    JUMP_ACTION, MUTE_ACTION, PAUSE_ACTION, REBINDABLE, THROW_ACTION,
+};
+pub use rng::{GameRng, GameplayConfig};
+pub use settings::{step_volume, DisplaySettings, Settings, VOLUME_STEP};
 
 /// A registry of some type of resource which enables lookup
 pub trait ResourceRegistry {
@@ -35,12 +48,62 @@ impl Default for Paused {
     }
 }
 
+/// How far the enemy is from the player, or `None` when nobody is being
+/// chased
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ChaseDistance(pub Option<f32>);
+
 const METERS_PER_DISTANCE_UNIT: f32 = 0.01;
+
+/// How many of the most recent runs to remember
+const RECENT_RUN_COUNT: usize = 10;
+
+/// How many entries fit on the leaderboard
+pub const LEADERBOARD_SIZE: usize = 10;
+
+fn now_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs())
+}
+
+/// The score of a finished run
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct RunScore {
+    distance: f32,
+    /// When the run finished, in seconds since the Unix epoch
+    pub timestamp: u64,
+}
+impl RunScore {
+    /// Get the distance traveled during the run, adjusted to meters.
+    pub fn get_score(&self) -> f32 {
+        self.distance * METERS_PER_DISTANCE_UNIT
+    }
+}
+
+/// A named run on the leaderboard
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct LeaderboardEntry {
+    pub name: String,
+    distance: f32,
+    /// When the run finished, in seconds since the Unix epoch
+    pub timestamp: u64,
+}
+impl LeaderboardEntry {
+    /// Get the distance traveled during the run, adjusted to meters.
+    pub fn get_score(&self) -> f32 {
+        self.distance * METERS_PER_DISTANCE_UNIT
+    }
+}
+
 #[derive(Debug, Default)]
 /// A struct which tracks the current score and the high score
 pub struct HighScore {
     distance: f32,
     max_distance: f32,
+    recent_runs: VecDeque<RunScore>,
+    /// Sorted from farthest to shortest
+    leaderboard: Vec<LeaderboardEntry>,
 }
 impl HighScore {
     /// Given the distance traveled, update the current game's score
@@ -66,6 +129,58 @@ impl HighScore {
     pub fn reset(&mut self) {
         self.distance = 0.0;
     }
+
+    /// Remember the current game's score as a finished run, forgetting
+    /// the oldest run if there are too many
+    pub fn record_run(&mut self) {
+        self.recent_runs.push_back(RunScore {
+            distance: self.distance,
+            timestamp: now_timestamp(),
+        });
+        while self.recent_runs.len() > RECENT_RUN_COUNT {
+            self.recent_runs.pop_front();
+        }
+    }
+
+    /// The most recent finished runs, oldest first
+    pub fn recent_runs(&self) -> impl Iterator<Item = &RunScore> {
+        self.recent_runs.iter()
+    }
+
+    /// Whether the current game's score is good enough for the leaderboard
+    pub fn makes_leaderboard(&self) -> bool {
+        self.distance > 0.0
+            && (self.leaderboard.len() < LEADERBOARD_SIZE
+                || self
+                    .leaderboard
+                    .last()
+                    .map_or(true, |entry| self.distance > entry.distance))
+    }
+
+    /// Put the current game's score on the leaderboard under the given
+    /// name, bumping off the lowest entry if the board is full
+    pub fn add_to_leaderboard(&mut self, name: String) {
+        let distance = self.distance;
+        let index = self
+            .leaderboard
+            .iter()
+            .position(|entry| distance > entry.distance)
+            .unwrap_or_else(|| self.leaderboard.len());
+        self.leaderboard.insert(
+            index,
+            LeaderboardEntry {
+                name,
+                distance,
+                timestamp: now_timestamp(),
+            },
+        );
+        self.leaderboard.truncate(LEADERBOARD_SIZE);
+    }
+
+    /// The leaderboard, from farthest to shortest
+    pub fn leaderboard(&self) -> &[LeaderboardEntry] {
+        &self.leaderboard
+    }
 }
 
 #[derive(Debug, Default)]
@@ -73,5 +188,25 @@ pub struct GameplayScoreDisplay {
     pub displays: Vec<Entity>,
 }
 
+/// The labels showing how many things the player has left to throw
+#[derive(Debug, Default)]
+pub struct GameplayAmmoDisplay {
+    pub displays: Vec<Entity>,
+}
+
 #[derive(Debug, Default)]
 pub struct CollisionEvent;
+
+/// Something which happened during gameplay, for things like sound
+/// effects to react to
+#[derive(Clone, Debug, PartialEq)]
+pub enum GameEvent {
+    /// A character started touching an obstacle of the given kind
+    ObstacleContact {
+        character: Entity,
+        obstacle: Entity,
+        kind: String,
+    },
+    /// The enemy caught the player
+    Caught,
+}
@@ -163,56 +163,30 @@ impl<'a> PrefabData<'a> for EnemyPrefab {
     }
 }
 
-#[derive(Copy, Clone, Debug, Default, Deserialize, Serialize)]
-pub struct CarPrefab;
-impl<'a> PrefabData<'a> for CarPrefab {
-    type SystemData = WriteStorage<'a, Car>;
-    type Result = ();
-
-    fn add_to_entity(
-        &self,
-        entity: Entity,
-        enemies: &mut Self::SystemData,
-        _entities: &[Entity],
-        _children: &[Entity],
-    ) -> Result<Self::Result, Error> {
-        enemies.insert(entity, Car::default())?;
-        Ok(())
-    }
-}
-
-#[derive(Copy, Clone, Debug, Default, Deserialize, Serialize)]
-pub struct MudPrefab;
-impl<'a> PrefabData<'a> for MudPrefab {
-    type SystemData = WriteStorage<'a, Mud>;
-    type Result = ();
-
-    fn add_to_entity(
-        &self,
-        entity: Entity,
-        enemies: &mut Self::SystemData,
-        _entities: &[Entity],
-        _children: &[Entity],
-    ) -> Result<Self::Result, Error> {
-        enemies.insert(entity, Mud::default())?;
-        Ok(())
-    }
+/// How an obstacle gets placed in the street. This is only read when
+/// spawning an obstacle, so it adds nothing to the entity itself.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ObstacleSpawnPrefab {
+    /// The name of the sprite sheet to draw the obstacle with, if it is
+    /// drawn at all
+    pub sprite_sheet: Option<String>,
+    /// How much to scale up the obstacle's sprite
+    pub scale: f32,
+    /// How likely this obstacle is to be picked, relative to the others
+    pub weight: f32,
 }
-
-#[derive(Copy, Clone, Debug, Default, Deserialize, Serialize)]
-pub struct DogPrefab;
-impl<'a> PrefabData<'a> for DogPrefab {
-    type SystemData = WriteStorage<'a, Dog>;
+impl<'a> PrefabData<'a> for ObstacleSpawnPrefab {
+    type SystemData = ();
     type Result = ();
 
     fn add_to_entity(
         &self,
-        entity: Entity,
-        enemies: &mut Self::SystemData,
+        _entity: Entity,
+        _system_data: &mut Self::SystemData,
         _entities: &[Entity],
         _children: &[Entity],
     ) -> Result<Self::Result, Error> {
-        enemies.insert(entity, Dog::default())?;
         Ok(())
     }
 }
@@ -220,10 +194,14 @@ impl<'a> PrefabData<'a> for DogPrefab {
 #[derive(Clone, Debug, Deserialize, PrefabData, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct CharacterPrefab {
+    animation: Option<Animation>,
     camera: Option<CameraAdapterPrefab>,
     enemy: Option<EnemyPrefab>,
+    facing: Option<Facing>,
     player: Option<PlayerPrefab>,
     position: Option<TransformAdapterPrefab>,
+    steering: Option<Steering>,
+    vision: Option<Vision>,
 }
 
 #[derive(Default)]
@@ -272,12 +250,13 @@ impl BackgroundPrefabRegistry {
 #[derive(Clone, Debug, Deserialize, PrefabData, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct ObstaclePrefab {
-    mud: Option<MudPrefab>,
-    mudposition: Option<TransformAdapterPrefab>,
-    car: Option<CarPrefab>,
-    carposition: Option<TransformAdapterPrefab>,
-    dog: Option<DogPrefab>,
-    dogposition: Option<TransformAdapterPrefab>,
+    obstacle: Option<Obstacle>,
+    spawn: ObstacleSpawnPrefab,
+    animation: Option<Animation>,
+    dog: Option<Dog>,
+    facing: Option<Facing>,
+    lane: Option<TrafficLane>,
+    position: Option<TransformAdapterPrefab>,
 }
 
 #[derive(Default)]
@@ -292,6 +271,39 @@ impl ObstaclePrefabRegistry {
     ) -> Option<<Self as super::ResourceRegistry>::ResourceType> {
         self.prefabs.get(name).cloned()
     }
+
+    /// Get every loaded obstacle prefab along with how to spawn it,
+    /// ordered by name so that spawning is deterministic
+    pub fn spawn_definitions<'a>(
+        &'a self,
+        storage: &'a AssetStorage<Prefab<ObstaclePrefab>>,
+    ) -> Vec<(&'a Handle<Prefab<ObstaclePrefab>>, &'a ObstacleSpawnPrefab)> {
+        let mut definitions = self
+            .prefabs
+            .iter()
+            .filter_map(|(name, handle)| {
+                let prefab = storage.get(handle)?.entities().next()?.data()?;
+                Some((name, handle, &prefab.spawn))
+            })
+            .collect::<Vec<_>>();
+        definitions.sort_by(|a, b| a.0.cmp(b.0));
+        definitions
+            .into_iter()
+            .map(|(_, handle, spawn)| (handle, spawn))
+            .collect()
+    }
+
+    /// Get the obstacle prefab with the given name along with how to
+    /// spawn it, if it has loaded
+    pub fn spawn_definition<'a>(
+        &'a self,
+        storage: &'a AssetStorage<Prefab<ObstaclePrefab>>,
+        name: &str,
+    ) -> Option<(&'a Handle<Prefab<ObstaclePrefab>>, &'a ObstacleSpawnPrefab)> {
+        let handle = self.prefabs.get(name)?;
+        let prefab = storage.get(handle)?.entities().next()?.data()?;
+        Some((handle, &prefab.spawn))
+    }
 }
 
 impl super::ResourceRegistry for ObstaclePrefabRegistry {
@@ -367,77 +379,85 @@ pub fn initialize_prefabs(world: &mut World) -> ProgressCounter {
             })
             .collect();
         world.insert(reg);
-        // Load Character Prefabs
-        {
-            let mut reg = CharacterPrefabRegistry::default();
-            let prefab_path = application_root_dir()
-                .unwrap()
-                .join("assets")
-                .join("prefabs")
-                .join("character");
-            let prefab_iter = std::fs::read_dir(prefab_path.to_str().unwrap()).unwrap();
-            reg.prefabs = prefab_iter
-                .filter_map(|entry| {
-                    if let Ok(file) = entry {
-                        let file = file.path();
-                        let filename = file.to_str()?;
-                        let filestem = file.file_stem()?.to_str()?.to_string();
-                        if file
-                            .extension()
-                            .map_or(false, |s| s.to_str() == Some("ron"))
-                        {
-                            Some((
-                                filestem,
-                                world.exec(|loader: PrefabLoader<'_, CharacterPrefab>| {
-                                    loader.load(filename, RonFormat, &mut counter)
-                                }),
-                            ))
-                        } else {
-                            None
-                        }
+    }
+    counter
+}
+
+/// Load the prefabs needed to simulate gameplay: the characters and the
+/// obstacles. Unlike `initialize_prefabs`, this does not need the UI to
+/// be set up, so it can be used without a window.
+pub fn initialize_gameplay_prefabs(world: &mut World) -> ProgressCounter {
+    let mut counter = ProgressCounter::new();
+    // Load Character Prefabs
+    {
+        let mut reg = CharacterPrefabRegistry::default();
+        let prefab_path = application_root_dir()
+            .unwrap()
+            .join("assets")
+            .join("prefabs")
+            .join("character");
+        let prefab_iter = std::fs::read_dir(prefab_path.to_str().unwrap()).unwrap();
+        reg.prefabs = prefab_iter
+            .filter_map(|entry| {
+                if let Ok(file) = entry {
+                    let file = file.path();
+                    let filename = file.to_str()?;
+                    let filestem = file.file_stem()?.to_str()?.to_string();
+                    if file
+                        .extension()
+                        .map_or(false, |s| s.to_str() == Some("ron"))
+                    {
+                        Some((
+                            filestem,
+                            world.exec(|loader: PrefabLoader<'_, CharacterPrefab>| {
+                                loader.load(filename, RonFormat, &mut counter)
+                            }),
+                        ))
                     } else {
                         None
                     }
-                })
-                .collect();
-            world.insert(reg);
-        }
+                } else {
+                    None
+                }
+            })
+            .collect();
+        world.insert(reg);
+    }
 
-        // Load Obstacle Prefabs
-        {
-            let mut reg = ObstaclePrefabRegistry::default();
-            let prefab_path = application_root_dir()
-                .unwrap()
-                .join("assets")
-                .join("prefabs")
-                .join("obstacle");
-            let prefab_iter = std::fs::read_dir(prefab_path.to_str().unwrap()).unwrap();
-            reg.prefabs = prefab_iter
-                .filter_map(|entry| {
-                    if let Ok(file) = entry {
-                        let file = file.path();
-                        let filename = file.to_str()?;
-                        let filestem = file.file_stem()?.to_str()?.to_string();
-                        if file
-                            .extension()
-                            .map_or(false, |s| s.to_str() == Some("ron"))
-                        {
-                            Some((
-                                filestem,
-                                world.exec(|loader: PrefabLoader<'_, ObstaclePrefab>| {
-                                    loader.load(filename, RonFormat, &mut counter)
-                                }),
-                            ))
-                        } else {
-                            None
-                        }
+    // Load Obstacle Prefabs
+    {
+        let mut reg = ObstaclePrefabRegistry::default();
+        let prefab_path = application_root_dir()
+            .unwrap()
+            .join("assets")
+            .join("prefabs")
+            .join("obstacle");
+        let prefab_iter = std::fs::read_dir(prefab_path.to_str().unwrap()).unwrap();
+        reg.prefabs = prefab_iter
+            .filter_map(|entry| {
+                if let Ok(file) = entry {
+                    let file = file.path();
+                    let filename = file.to_str()?;
+                    let filestem = file.file_stem()?.to_str()?.to_string();
+                    if file
+                        .extension()
+                        .map_or(false, |s| s.to_str() == Some("ron"))
+                    {
+                        Some((
+                            filestem,
+                            world.exec(|loader: PrefabLoader<'_, ObstaclePrefab>| {
+                                loader.load(filename, RonFormat, &mut counter)
+                            }),
+                        ))
                     } else {
                         None
                     }
-                })
-                .collect();
-            world.insert(reg);
-        }
+                } else {
+                    None
+                }
+            })
+            .collect();
+        world.insert(reg);
     }
     counter
 }
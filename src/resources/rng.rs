@@ -0,0 +1,52 @@
+use rand::{rngs::StdRng, Error, RngCore, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+/// Gameplay settings, loaded from `config/gameplay.ron`
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+pub struct GameplayConfig {
+    /// The seed every level is generated from. A new seed is picked for
+    /// every run when this is `None`.
+    pub seed: Option<u64>,
+}
+
+/// The random number generator which every spawning system draws from,
+/// so that the same seed and the same inputs always produce the same level
+pub struct GameRng {
+    seed: u64,
+    rng: StdRng,
+}
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        GameRng {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// The seed this generator was started from, for sharing a level
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::new(rand::random())
+    }
+}
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
@@ -0,0 +1,128 @@
+//! Saves the high score between sessions, in a file in the user's data
+//! directory
+
+use super::{HighScore, LeaderboardEntry, RunScore, LEADERBOARD_SIZE};
+use amethyst::config::Config;
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Ordering,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Bump this whenever the layout of `SaveFile` changes
+const SAVE_VERSION: u32 = 2;
+
+const SAVE_DIR_NAME: &str = "stranger-danger";
+const SAVE_FILE_NAME: &str = "save.ron";
+
+/// Just enough of a save file to find out which version wrote it
+#[derive(Debug, Deserialize, Serialize)]
+struct SaveHeader {
+    version: u32,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct SaveFile {
+    version: u32,
+    max_distance: f32,
+    recent_runs: Vec<RunScore>,
+    /// Added in version 2
+    #[serde(default)]
+    leaderboard: Vec<LeaderboardEntry>,
+}
+
+/// The directory the game saves into, if the platform has one
+pub fn save_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join(SAVE_DIR_NAME))
+}
+
+fn save_path() -> Option<PathBuf> {
+    save_dir().map(|dir| dir.join(SAVE_FILE_NAME))
+}
+
+/// Write `value` to `path`, going through a temporary file first so a
+/// crash can't leave a half-written file behind
+pub(super) fn write_file<T: Config>(value: &T, path: &Path) -> Result<(), String> {
+    let temp_path = path.with_extension("ron.tmp");
+    path.parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .map_err(|e| e.to_string())
+        .and_then(|_| value.write(&temp_path).map_err(|e| e.to_string()))
+        .and_then(|_| fs::rename(&temp_path, path).map_err(|e| e.to_string()))
+}
+
+/// Move an unreadable file out of the way, so that it isn't overwritten
+/// and can still be recovered by hand
+pub(super) fn back_up_file(path: &Path, error: &str) {
+    let backup = path.with_extension("ron.bak");
+    log::warn!(
+        "Couldn't read {}, moving it to {}: {}",
+        path.display(),
+        backup.display(),
+        error
+    );
+    fs::rename(path, &backup).unwrap_or_else(|e| log::warn!("Error backing up file: {}", e));
+}
+
+/// Load the saved high score. A missing save starts from scratch, and an
+/// unreadable one is moved aside so that it isn't overwritten.
+pub fn load_high_score() -> HighScore {
+    let path = match save_path() {
+        Some(path) => path,
+        None => {
+            log::warn!("No data directory found, so scores won't be saved");
+            return HighScore::default();
+        }
+    };
+    let bytes = match fs::read(&path) {
+        Ok(bytes) => bytes,
+        Err(_) => return HighScore::default(),
+    };
+    let save = match SaveHeader::load_bytes(&bytes) {
+        // version 1 saves only lack the leaderboard, which defaults to empty
+        Ok(SaveHeader { version: 1 })
+        | Ok(SaveHeader {
+            version: SAVE_VERSION,
+        }) => SaveFile::load_bytes(&bytes).map_err(|e| e.to_string()),
+        Ok(SaveHeader { version }) => Err(format!("unsupported save version {}", version)),
+        Err(e) => Err(e.to_string()),
+    };
+    match save {
+        Ok(mut save) => {
+            save.leaderboard.sort_by(|a, b| {
+                b.distance
+                    .partial_cmp(&a.distance)
+                    .unwrap_or(Ordering::Equal)
+            });
+            save.leaderboard.truncate(LEADERBOARD_SIZE);
+            HighScore {
+                distance: 0.0,
+                max_distance: save.max_distance,
+                recent_runs: save.recent_runs.into_iter().collect(),
+                leaderboard: save.leaderboard,
+            }
+        }
+        Err(e) => {
+            back_up_file(&path, &e);
+            HighScore::default()
+        }
+    }
+}
+
+/// Write the high score to the save file, logging any errors
+pub fn save_high_score(high_score: &HighScore) {
+    let path = match save_path() {
+        Some(path) => path,
+        None => return,
+    };
+    let save = SaveFile {
+        version: SAVE_VERSION,
+        max_distance: high_score.max_distance,
+        recent_runs: high_score.recent_runs.iter().cloned().collect(),
+        leaderboard: high_score.leaderboard.clone(),
+    };
+    if let Err(e) = write_file(&save, &path) {
+        log::warn!("Error saving scores to {}: {}", path.display(), e);
+    }
+}
@@ -0,0 +1,225 @@
+//! The player's settings, kept between sessions in the same directory as
+//! the save file
+
+use super::{
+    controls::default_bindings,
+    save::{back_up_file, save_dir, write_file},
+    Controls,
+};
+use amethyst::{
+    audio::AudioSink,
+    config::Config,
+    input::{Bindings, InputHandler, StringBindings},
+    prelude::*,
+    window::DisplayConfig,
+};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::PathBuf};
+
+/// Bump this whenever the layout of `Settings` changes
+const SETTINGS_VERSION: u32 = 2;
+
+const SETTINGS_FILE_NAME: &str = "settings.ron";
+
+/// How much the volume buttons in the options change the volume by
+pub const VOLUME_STEP: f32 = 0.1;
+
+/// Just enough of a settings file to find out which version wrote it
+#[derive(Debug, Deserialize, Serialize)]
+struct SettingsHeader {
+    version: u32,
+}
+
+/// Overrides for `config/display.ron`
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct DisplaySettings {
+    /// The size of the window, if it differs from the default
+    pub dimensions: Option<(u32, u32)>,
+    pub maximized: bool,
+}
+impl DisplaySettings {
+    /// Apply these settings on top of the display config
+    pub fn apply(&self, config: &mut DisplayConfig) {
+        if let Some(dimensions) = self.dimensions {
+            config.dimensions = Some(dimensions);
+        }
+        config.maximized |= self.maximized;
+    }
+}
+
+/// Options the player can change, which are remembered between sessions
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Settings {
+    version: u32,
+    pub controls: Controls,
+    /// The bindings used when `controls` is `Controls::Custom`. Added in
+    /// version 2.
+    pub custom_bindings: Option<Bindings<StringBindings>>,
+    /// Between 0 and 1
+    pub music_volume: f32,
+    /// Between 0 and 1
+    pub sfx_volume: f32,
+    /// Silences both music and sound effects, without losing the volumes
+    pub muted: bool,
+    pub display: DisplaySettings,
+}
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            version: SETTINGS_VERSION,
+            controls: Controls::default(),
+            custom_bindings: None,
+            music_volume: 1.0,
+            sfx_volume: 1.0,
+            muted: false,
+            display: DisplaySettings::default(),
+        }
+    }
+}
+impl Settings {
+    /// Load the player's settings. Missing settings use the defaults, and
+    /// unreadable ones are moved aside so that they aren't overwritten.
+    pub fn load_or_default() -> Self {
+        let path = match settings_path() {
+            Some(path) => path,
+            None => return Settings::default(),
+        };
+        let bytes = match fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(_) => return Settings::default(),
+        };
+        let settings = match SettingsHeader::load_bytes(&bytes) {
+            // version 1 settings only lack custom bindings
+            Ok(SettingsHeader { version: 1 })
+            | Ok(SettingsHeader {
+                version: SETTINGS_VERSION,
+            }) => Settings::load_bytes(&bytes).map_err(|e| e.to_string()),
+            Ok(SettingsHeader { version }) => {
+                Err(format!("unsupported settings version {}", version))
+            }
+            Err(e) => Err(e.to_string()),
+        };
+        let mut settings = settings.unwrap_or_else(|e| {
+            back_up_file(&path, &e);
+            Settings::default()
+        });
+        settings.version = SETTINGS_VERSION;
+        if let Some(Err(e)) = settings
+            .custom_bindings
+            .as_mut()
+            .map(Bindings::check_invariants)
+        {
+            log::warn!("Ignoring invalid custom bindings: {:?}", e);
+            settings.controls = Controls::default();
+            settings.custom_bindings = None;
+        }
+        settings.merge_default_bindings();
+        settings
+    }
+
+    /// Give any axes and actions missing from the custom bindings their
+    /// defaults from `config/inputs.ron`, so that bindings saved by an
+    /// older version of the game still have everything added since
+    fn merge_default_bindings(&mut self) {
+        let (custom, defaults) = match (self.custom_bindings.as_mut(), default_bindings()) {
+            (Some(custom), Some(defaults)) => (custom, defaults),
+            _ => return,
+        };
+        let missing_axes = defaults
+            .axes()
+            .filter(|axis| custom.axis(*axis).is_none())
+            .cloned()
+            .collect::<Vec<_>>();
+        for axis in missing_axes {
+            if let Some(binding) = defaults.axis(&axis) {
+                if let Err(e) = custom.insert_axis(axis.clone(), binding.clone()) {
+                    log::warn!("Couldn't bind {} by default: {:?}", axis, e);
+                }
+            }
+        }
+        let missing_actions = defaults
+            .actions()
+            .filter(|action| custom.action_bindings(*action).next().is_none())
+            .cloned()
+            .collect::<Vec<_>>();
+        for action in missing_actions {
+            for combo in defaults.action_bindings(&action) {
+                if let Err(e) = custom.insert_action_binding(action.clone(), combo.to_vec()) {
+                    log::warn!("Couldn't bind {} by default: {:?}", action, e);
+                }
+            }
+        }
+    }
+
+    /// Write the settings to disk, logging any errors
+    pub fn save(&self) {
+        if let Some(path) = settings_path() {
+            if let Err(e) = write_file(self, &path) {
+                log::warn!("Error saving settings to {}: {}", path.display(), e);
+            }
+        }
+    }
+
+    /// Apply the controls and volume to the world
+    pub fn apply(&self, world: &mut World) {
+        self.apply_controls(world);
+        self.apply_volume(world);
+    }
+
+    /// Set the music's volume, if there is any audio output
+    pub fn apply_volume(&self, world: &World) {
+        if let Some(mut sink) = world.try_fetch_mut::<AudioSink>() {
+            sink.set_volume(self.music_output_volume());
+        }
+    }
+
+    /// The volume music should play at, taking muting into account
+    pub fn music_output_volume(&self) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.music_volume
+        }
+    }
+
+    /// The volume sound effects should play at, taking muting into account
+    pub fn sfx_output_volume(&self) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.sfx_volume
+        }
+    }
+
+    /// Go back to the default control scheme, forgetting any custom
+    /// bindings. Call `apply_controls` afterwards to use them.
+    pub fn reset_controls(&mut self) {
+        self.controls = Controls::default();
+        self.custom_bindings = None;
+    }
+
+    /// Switch the world's bindings to the chosen controls
+    pub fn apply_controls(&self, world: &mut World) {
+        match (self.controls, &self.custom_bindings) {
+            (Controls::Custom, Some(bindings)) => {
+                world
+                    .write_resource::<InputHandler<StringBindings>>()
+                    .bindings = bindings.clone();
+            }
+            (controls, _) => controls.set_control_scheme(world),
+        }
+    }
+}
+
+/// Move a volume by `steps` of `VOLUME_STEP`, keeping it between 0 and 1
+pub fn step_volume(volume: f32, steps: i32) -> f32 {
+    // rounding stops the steps from drifting
+    let steps_now = (volume / VOLUME_STEP).round() as i32;
+    ((steps_now + steps) as f32 * VOLUME_STEP).max(0.0).min(1.0)
+}
+
+fn settings_path() -> Option<PathBuf> {
+    save_dir().map(|dir| dir.join(SETTINGS_FILE_NAME))
+}
@@ -0,0 +1,261 @@
+//! A headless harness which runs the gameplay systems without a window,
+//! so gameplay behaviour can be checked without launching the game.
+//!
+//! Build the game with `--no-default-features --features empty` to avoid
+//! needing a graphics backend.
+
+use crate::{
+    resources::{
+        prefabs::{initialize_gameplay_prefabs, CharacterPrefabRegistry},
+        GameRng, GameplayConfig, HighScore, ResourceRegistry,
+    },
+    systems,
+};
+use amethyst::{
+    assets::{Loader, ProgressCounter},
+    core::{transform::TransformBundle, ArcThreadPool, Time},
+    ecs::Entity,
+    input::{
+        Axis, ControllerAxis, ControllerEvent, InputBundle, InputEvent, InputHandler,
+        StringBindings,
+    },
+    prelude::*,
+    shrev::EventChannel,
+    utils::application_root_dir,
+    window::ScreenDimensions,
+};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// The id of the virtual controller used to feed scripted input
+const SCRIPTED_CONTROLLER: u32 = 0;
+
+/// How long to wait for the prefabs to load before giving up
+const LOAD_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Matches the window size in `config/display.ron`
+const SCREEN_WIDTH: u32 = 1200;
+const SCREEN_HEIGHT: u32 = 900;
+
+/// Configures and builds a `Simulation`
+pub struct SimulationBuilder {
+    frame_time: f32,
+    seed: u64,
+}
+impl Default for SimulationBuilder {
+    fn default() -> Self {
+        SimulationBuilder {
+            frame_time: 1.0 / 60.0,
+            seed: 0,
+        }
+    }
+}
+impl SimulationBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set how many seconds each simulated frame lasts
+    pub fn with_frame_time(mut self, seconds: f32) -> Self {
+        self.frame_time = seconds;
+        self
+    }
+
+    /// Set the seed the level is generated from
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Build the world and dispatcher, load the character and obstacle
+    /// prefabs, and spawn the player and the enemy
+    pub fn build<'a, 'b>(self) -> amethyst::Result<Simulation<'a, 'b>> {
+        let mut world = World::new();
+        let pool: ArcThreadPool = Arc::new(rayon::ThreadPoolBuilder::new().build()?);
+        let assets_dir = application_root_dir()?.join("assets");
+        world.insert(Loader::new(assets_dir, pool.clone()));
+        world.insert(pool);
+        world.insert(EventChannel::<amethyst::winit::Event>::default());
+        world.insert(Time::default());
+        world.insert(ScreenDimensions::new(SCREEN_WIDTH, SCREEN_HEIGHT, 1.0));
+        world.insert(GameplayConfig {
+            seed: Some(self.seed),
+        });
+        world.insert(GameRng::new(self.seed));
+
+        let game_data = systems::with_gameplay_systems(
+            GameDataBuilder::default()
+                .with_bundle(InputBundle::<StringBindings>::new())?
+                .with_bundle(TransformBundle::new())?,
+        )
+        .build(&mut world);
+
+        let mut simulation = Simulation {
+            world,
+            game_data,
+            frame_time: self.frame_time,
+            player: None,
+            enemy: None,
+        };
+        simulation.init_input();
+        let counter = initialize_gameplay_prefabs(&mut simulation.world);
+        simulation.wait_for(&counter)?;
+        simulation.init_characters();
+        Ok(simulation)
+    }
+}
+
+/// A running game without a window. Every call to `step` runs the
+/// gameplay dispatcher once.
+pub struct Simulation<'a, 'b> {
+    world: World,
+    game_data: GameData<'a, 'b>,
+    frame_time: f32,
+    player: Option<Entity>,
+    enemy: Option<Entity>,
+}
+
+impl<'a, 'b> Simulation<'a, 'b> {
+    /// The world being simulated, for making assertions
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    /// The world being simulated, for setting up a scenario
+    pub fn world_mut(&mut self) -> &mut World {
+        &mut self.world
+    }
+
+    pub fn player(&self) -> Entity {
+        self.player.expect("Simulation has no player")
+    }
+
+    pub fn enemy(&self) -> Entity {
+        self.enemy.expect("Simulation has no enemy")
+    }
+
+    /// Hold the `horizontal` and `vertical` axes at the given values,
+    /// each between -1 and 1, until they are set again
+    pub fn set_axes(&mut self, horizontal: f32, vertical: f32) {
+        self.send_axis(ControllerAxis::LeftX, horizontal);
+        self.send_axis(ControllerAxis::LeftY, vertical);
+    }
+
+    /// Run a single frame
+    pub fn step(&mut self) {
+        {
+            let mut time = self.world.write_resource::<Time>();
+            time.set_delta_seconds(self.frame_time);
+            time.increment_frame_number();
+        }
+        self.game_data.update(&self.world);
+        self.world.maintain();
+    }
+
+    /// Run the given number of frames with the current input
+    pub fn run(&mut self, frames: usize) {
+        for _ in 0..frames {
+            self.step();
+        }
+    }
+
+    /// Run each `(horizontal, vertical, frames)` entry of a script in
+    /// order, holding the axes for that many frames
+    pub fn run_script(&mut self, script: &[(f32, f32, usize)]) {
+        for &(horizontal, vertical, frames) in script {
+            self.set_axes(horizontal, vertical);
+            self.run(frames);
+        }
+    }
+
+    /// Bind the movement axes to a virtual controller so that scripted
+    /// input gives exact axis values
+    fn init_input(&mut self) {
+        let mut input = self.world.write_resource::<InputHandler<StringBindings>>();
+        for &(name, axis) in &[
+            ("horizontal", ControllerAxis::LeftX),
+            ("vertical", ControllerAxis::LeftY),
+        ] {
+            input
+                .bindings
+                .insert_axis(
+                    name,
+                    Axis::Controller {
+                        controller_id: SCRIPTED_CONTROLLER,
+                        axis,
+                        invert: false,
+                        dead_zone: 0.0,
+                    },
+                )
+                .expect("error binding controls");
+        }
+        let mut events = self
+            .world
+            .write_resource::<EventChannel<InputEvent<StringBindings>>>();
+        input.send_controller_event(
+            &ControllerEvent::ControllerConnected {
+                which: SCRIPTED_CONTROLLER,
+            },
+            &mut events,
+        );
+    }
+
+    fn send_axis(&mut self, axis: ControllerAxis, value: f32) {
+        let mut input = self.world.write_resource::<InputHandler<StringBindings>>();
+        let mut events = self
+            .world
+            .write_resource::<EventChannel<InputEvent<StringBindings>>>();
+        input.send_controller_event(
+            &ControllerEvent::ControllerAxisMoved {
+                which: SCRIPTED_CONTROLLER,
+                axis,
+                value: value.max(-1.0).min(1.0),
+            },
+            &mut events,
+        );
+    }
+
+    /// Run the dispatcher until everything tracked by `counter` has loaded
+    fn wait_for(&mut self, counter: &ProgressCounter) -> amethyst::Result<()> {
+        let start = Instant::now();
+        while counter.num_loading() != 0 {
+            if start.elapsed() > LOAD_TIMEOUT {
+                return Err(amethyst::Error::from_string(
+                    "Timed out loading gameplay prefabs",
+                ));
+            }
+            self.game_data.update(&self.world);
+            self.world.maintain();
+        }
+        if counter.num_failed() != 0 {
+            return Err(amethyst::Error::from_string(format!(
+                "Failed loading gameplay prefabs: {:#?}",
+                counter.errors()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Spawn the player and the enemy the same way `GameplayState` does,
+    /// minus their sprites
+    fn init_characters(&mut self) {
+        let (player_prefab, enemy_prefab) = {
+            let registry = self.world.read_resource::<CharacterPrefabRegistry>();
+            (
+                registry
+                    .find(&self.world, "player")
+                    .expect("Couldn't find player prefab"),
+                registry
+                    .find(&self.world, "enemy")
+                    .expect("Couldn't find enemy prefab"),
+            )
+        };
+        self.player = Some(self.world.create_entity().with(player_prefab).build());
+        self.enemy = Some(self.world.create_entity().with(enemy_prefab).build());
+        self.world.write_resource::<HighScore>().reset();
+        // Let the prefab loader attach the components before any input
+        self.step();
+    }
+}
@@ -1,7 +1,11 @@
 use crate::{
-    resources::{prefabs::UiPrefabRegistry, ResourceRegistry},
+    resources::{
+        audio::{play_playlist, CUTSCENE_PLAYLIST},
+        prefabs::UiPrefabRegistry,
+        ResourceRegistry, CONFIRM_ACTION,
+    },
     states::GameplayState,
-    utils,
+    utils::{self, is_action_down},
 };
 use amethyst::{
     core::transform::ParentHierarchy,
@@ -58,6 +62,9 @@ impl SimpleState for CutsceneState {
         _data: StateData<'_, GameData<'_, '_>>,
         event: StateEvent,
     ) -> SimpleTrans {
+        if is_action_down(&event, CONFIRM_ACTION) {
+            return Trans::Switch(Box::new(GameplayState::default()));
+        }
         match event {
             StateEvent::Window(event) => {
                 if input::is_key_down(&event, VirtualKeyCode::Escape) {
@@ -89,6 +96,10 @@ impl SimpleState for CutsceneState {
         Trans::None
     }
 
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        play_playlist(data.world, CUTSCENE_PLAYLIST);
+    }
+
     fn on_stop(&mut self, mut data: StateData<'_, GameData<'_, '_>>) {
         self.deinit_entities(&mut data.world);
     }
@@ -1,13 +1,13 @@
 use crate::{
-    resources::{prefabs::UiPrefabRegistry, HighScore, ResourceRegistry},
+    resources::{prefabs::UiPrefabRegistry, save::save_high_score, HighScore, ResourceRegistry},
     states::GameplayState,
-    utils::delete_hierarchy,
+    utils::{confirm_as_click, delete_hierarchy},
 };
 
 use amethyst::{
     ecs::Entity,
     prelude::*,
-    ui::{UiEvent, UiEventType, UiFinder, UiText},
+    ui::{Selected, UiEvent, UiEventType, UiFinder, UiText},
 };
 
 const GAME_OVER_ID: &str = "game-over";
@@ -16,6 +16,11 @@ const PLAY_AGAIN_BUTTON: &str = "play_again";
 const RETURN_TO_MENU_BUTTON: &str = "return_to_menu";
 const SCORE_LABEL: &str = "score";
 const HIGH_SCORE_LABEL: &str = "high_score";
+const NAME_ENTRY: &str = "name_entry";
+const NAME_FIELD: &str = "name";
+
+/// The name used if the player leaves without typing one
+const DEFAULT_NAME: &str = "???";
 
 #[derive(Default)]
 pub struct GameOverState {
@@ -24,23 +29,42 @@ pub struct GameOverState {
     return_to_menu_button: Option<Entity>,
     score_label: Option<Entity>,
     high_score_label: Option<Entity>,
+    name_entry: Option<Entity>,
+    name_field: Option<Entity>,
 }
 
 impl SimpleState for GameOverState {
     fn on_start(&mut self, mut data: StateData<'_, GameData<'_, '_>>) {
+        {
+            let mut high_score = data.world.write_resource::<HighScore>();
+            high_score.record_run();
+            save_high_score(&high_score);
+        }
         self.init_gui(&mut data);
     }
 
     fn on_stop(&mut self, mut data: StateData<'_, GameData<'_, '_>>) {
+        // a top 10 run still makes the leaderboard if the player leaves
+        // without entering a name
+        self.submit_name(data.world);
         self.deinit_gui(&mut data);
     }
 
     fn handle_event(
         &mut self,
-        _data: StateData<'_, GameData<'_, '_>>,
+        data: StateData<'_, GameData<'_, '_>>,
         event: StateEvent,
     ) -> SimpleTrans {
-        match event {
+        match confirm_as_click(data.world, event, self.play_again_button) {
+            StateEvent::Ui(UiEvent {
+                event_type: UiEventType::ValueCommit,
+                target,
+            }) => {
+                if self.name_field.map_or(false, |field| field == target) {
+                    self.submit_name(data.world);
+                }
+                Trans::None
+            }
             StateEvent::Ui(UiEvent {
                 event_type: UiEventType::Click,
                 target,
@@ -78,18 +102,61 @@ impl GameOverState {
             self.return_to_menu_button = ui_finder.find(RETURN_TO_MENU_BUTTON);
             self.score_label = ui_finder.find(SCORE_LABEL);
             self.high_score_label = ui_finder.find(HIGH_SCORE_LABEL);
+            self.name_entry = ui_finder.find(NAME_ENTRY);
+            self.name_field = ui_finder.find(NAME_FIELD);
         });
-        let mut uitext = data.world.write_storage::<UiText>();
-        let high_score = data.world.read_resource::<HighScore>();
-        if let Some(text) = self.score_label.and_then(|label| uitext.get_mut(label)) {
-            text.text = format!("You evaded him for: {:.2} meters", high_score.get_score());
+        let makes_leaderboard = {
+            let mut uitext = data.world.write_storage::<UiText>();
+            let high_score = data.world.read_resource::<HighScore>();
+            if let Some(text) = self.score_label.and_then(|label| uitext.get_mut(label)) {
+                text.text = format!("You evaded him for: {:.2} meters", high_score.get_score());
+            }
+            if let Some(text) = self
+                .high_score_label
+                .and_then(|label| uitext.get_mut(label))
+            {
+                text.text = format!("Your record is: {:.2} meters", high_score.get_high_score());
+            }
+            high_score.makes_leaderboard()
+        };
+        if makes_leaderboard {
+            // lets the player start typing without clicking the field
+            if let Some(field) = self.name_field {
+                data.world
+                    .write_storage::<Selected>()
+                    .insert(field, Selected)
+                    .unwrap_or_else(|e| log::warn!("Error selecting name field: {}", e));
+            }
+        } else if let Some(entry) = self.name_entry.take() {
+            delete_hierarchy(data.world, entry);
+            self.name_field = None;
         }
-        if let Some(text) = self
-            .high_score_label
-            .and_then(|label| uitext.get_mut(label))
+    }
+
+    /// Put this run on the leaderboard under the typed name, and hide the
+    /// name entry. Does nothing if the name was already submitted.
+    fn submit_name(&mut self, world: &mut World) {
+        let entry = match self.name_entry.take() {
+            Some(entry) => entry,
+            None => return,
+        };
+        let name = self
+            .name_field
+            .take()
+            .and_then(|field| {
+                world
+                    .read_storage::<UiText>()
+                    .get(field)
+                    .map(|text| text.text.trim().to_string())
+            })
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| DEFAULT_NAME.to_string());
         {
-            text.text = format!("Your record is: {:.2} meters", high_score.get_high_score());
+            let mut high_score = world.write_resource::<HighScore>();
+            high_score.add_to_leaderboard(name);
+            save_high_score(&high_score);
         }
+        delete_hierarchy(world, entry);
     }
 
     fn deinit_gui(&mut self, data: &mut StateData<GameData>) {
@@ -99,6 +166,8 @@ impl GameOverState {
             self.return_to_menu_button = None;
             self.score_label = None;
             self.high_score_label = None;
+            self.name_entry = None;
+            self.name_field = None;
         }
         data.data.update(data.world);
     }
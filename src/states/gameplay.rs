@@ -1,15 +1,19 @@
 // neccesary imports
 use crate::{
+    components::{Projectile, Skateboard},
     resources::{
+        audio::{play_playlist, GAMEPLAY_PLAYLIST},
         prefabs::{CharacterPrefabRegistry, UiPrefabRegistry},
         sprites::SpriteSheetRegister,
-        CollisionEvent, GameplayScoreDisplay, HighScore, QuitToMenu, ResourceRegistry,
+        ChaseDistance, CollisionEvent, GameRng, GameplayAmmoDisplay, GameplayConfig,
+        GameplayScoreDisplay, HighScore, QuitToMenu, ResourceRegistry, PAUSE_ACTION,
     },
     states::{GameOverState, PauseState},
-    utils::delete_hierarchy,
+    utils::{delete_hierarchy, is_action_down},
 };
 use amethyst::{
-    ecs::{Entity, Read},
+    core::{transform::Parent, Hidden, Transform},
+    ecs::{Entity, Join, Read},
     input::{get_key, is_close_requested, is_key_down, VirtualKeyCode},
     prelude::*,
 };
@@ -21,17 +25,22 @@ pub struct GameplayState {
     player: Option<Entity>,
     enemy: Option<Entity>,
     score: Option<Entity>,
+    ammo: Option<Entity>,
     reader: Option<ReaderId<CollisionEvent>>,
 }
 
 const PLAYER_SHEET_ID: &str = "gamer";
+const SKATEBOARD_SHEET_ID: &str = "skateboard";
 const ENEMY_SHEET_ID: &str = "walkRight";
 
 impl SimpleState for GameplayState {
     fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        play_playlist(data.world, GAMEPLAY_PLAYLIST);
+        self.init_rng(data.world);
         self.init_player(data.world);
         self.init_enemy(data.world);
         self.init_score(data.world);
+        self.init_ammo(data.world);
         self.reader = Some(
             data.world
                 .fetch_mut::<EventChannel<CollisionEvent>>()
@@ -44,6 +53,8 @@ impl SimpleState for GameplayState {
 
     fn on_stop(&mut self, mut data: StateData<'_, GameData<'_, '_>>) {
         self.deinit_sprites(&mut data.world);
+        self.deinit_projectiles(&mut data.world);
+        data.world.insert(ChaseDistance(None));
         self.reader = None
     }
 
@@ -70,6 +81,9 @@ impl SimpleState for GameplayState {
         data: StateData<'_, GameData<'_, '_>>,
         event: StateEvent,
     ) -> SimpleTrans {
+        if is_action_down(&event, PAUSE_ACTION) {
+            return Trans::Push(Box::new(PauseState::default()));
+        }
         if let StateEvent::Window(event) = &event {
             // Check if the window should be closed
             if is_close_requested(&event) {
@@ -101,6 +115,16 @@ impl SimpleState for GameplayState {
 }
 
 impl GameplayState {
+    /// Pick the seed this run's level is generated from
+    fn init_rng(&mut self, world: &mut World) {
+        let config = *world
+            .entry::<GameplayConfig>()
+            .or_insert_with(Default::default);
+        let seed = config.seed.unwrap_or_else(rand::random);
+        log::info!("Generating level from seed {}", seed);
+        world.insert(GameRng::new(seed));
+    }
+
     fn init_player(&mut self, world: &mut World) {
         let sprite_render = world
             .read_resource::<SpriteSheetRegister>()
@@ -110,13 +134,32 @@ impl GameplayState {
             .read_resource::<CharacterPrefabRegistry>()
             .find(world, "player")
             .expect("Couldn't find player prefab");
-        self.player = Some(
+        let player = world
+            .create_entity()
+            .with(sprite_render)
+            .with(player_prefab)
+            .build();
+        self.player = Some(player);
+
+        // the skateboard rides along under the player, hidden until picked up
+        if let Some(sprite_render) =
+            world
+                .read_resource::<SpriteSheetRegister>()
+                .find_sprite(world, SKATEBOARD_SHEET_ID, 0)
+        {
+            let mut transform = Transform::default();
+            transform.set_translation_xyz(0.0, -12.0, -0.1);
+            // the skateboard's sprites are drawn smaller than the player's
+            *transform.scale_mut() *= 3.0;
             world
                 .create_entity()
+                .with(Skateboard)
+                .with(Parent { entity: player })
+                .with(transform)
                 .with(sprite_render)
-                .with(player_prefab)
-                .build(),
-        );
+                .with(Hidden)
+                .build();
+        }
     }
 
     fn init_enemy(&mut self, world: &mut World) {
@@ -151,6 +194,25 @@ impl GameplayState {
                 displays.remove(index);
             }
         }
+        if let Some(ammo) = self.ammo.take() {
+            delete_hierarchy(world, ammo);
+            let displays = &mut world.write_resource::<GameplayAmmoDisplay>().displays;
+            if let Some(index) = displays.iter().position(|&e| e == ammo) {
+                displays.remove(index);
+            }
+        }
+    }
+
+    /// Remove any phones still in the air, so that they don't carry over
+    /// into the next run
+    fn deinit_projectiles(&mut self, world: &mut World) {
+        let projectiles = (&world.entities(), &world.read_storage::<Projectile>())
+            .join()
+            .map(|(entity, _)| entity)
+            .collect::<Vec<_>>();
+        world
+            .delete_entities(&projectiles)
+            .expect("Error removing projectiles");
     }
 
     fn init_score(&mut self, world: &mut World) {
@@ -164,4 +226,16 @@ impl GameplayState {
             .displays
             .push(self.score.unwrap());
     }
+
+    fn init_ammo(&mut self, world: &mut World) {
+        let prefab = world
+            .read_resource::<UiPrefabRegistry>()
+            .find(world, "gameplay-ammo")
+            .expect("Couldn't load gameplay ammo prefab");
+        self.ammo = Some(world.create_entity().with(prefab).build());
+        world
+            .write_resource::<GameplayAmmoDisplay>()
+            .displays
+            .push(self.ammo.unwrap());
+    }
 }
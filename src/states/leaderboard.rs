@@ -0,0 +1,145 @@
+//! Lists the best runs, with who ran them and when
+
+use crate::{
+    resources::{prefabs::UiPrefabRegistry, HighScore, ResourceRegistry},
+    utils::{self, confirm_as_click},
+};
+use amethyst::{
+    core::transform::ParentHierarchy,
+    ecs::Entity,
+    input::{self, VirtualKeyCode},
+    prelude::*,
+    ui::{UiEvent, UiEventType, UiFinder, UiText, UiTransform},
+};
+use chrono::{Local, TimeZone};
+
+const LEADERBOARD_ID: &str = "leaderboard";
+const LEADERBOARD_ROW_ID: &str = "leaderboard_row";
+
+const BACK_BUTTON: &str = "back";
+const NO_ENTRIES_LABEL: &str = "no_entries";
+const RANK_LABEL: &str = "rank";
+const NAME_LABEL: &str = "name";
+const DISTANCE_LABEL: &str = "distance";
+const DATE_LABEL: &str = "date";
+/// Shown instead of the date of a run whose time can't be read
+const UNKNOWN_DATE: &str = "----------";
+
+/// The height of each row, and where the first one goes
+const ROW_HEIGHT: f32 = 50.0;
+const FIRST_ROW_Y: f32 = -180.0;
+
+#[derive(Default)]
+pub struct LeaderboardState {
+    root_entity: Option<Entity>,
+    back_button: Option<Entity>,
+    rows: Vec<Entity>,
+}
+
+impl SimpleState for LeaderboardState {
+    fn on_start(&mut self, mut data: StateData<'_, GameData<'_, '_>>) {
+        self.init_gui(&mut data);
+    }
+
+    fn on_stop(&mut self, mut data: StateData<'_, GameData<'_, '_>>) {
+        self.deinit_gui(&mut data);
+    }
+
+    fn handle_event(
+        &mut self,
+        data: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        match confirm_as_click(data.world, event, self.back_button) {
+            StateEvent::Ui(UiEvent {
+                event_type: UiEventType::Click,
+                target,
+            }) if self.back_button.map_or(false, |button| button == target) => Trans::Pop,
+            StateEvent::Window(event) if input::is_key_down(&event, VirtualKeyCode::Escape) => {
+                Trans::Pop
+            }
+            _ => Trans::None,
+        }
+    }
+
+    fn update(&mut self, data: &mut StateData<GameData>) -> SimpleTrans {
+        data.data.update(&data.world);
+        Trans::None
+    }
+}
+
+impl LeaderboardState {
+    fn init_gui(&mut self, data: &mut StateData<GameData>) {
+        let (leaderboard_prefab, row_prefab) = {
+            let registry = data.world.read_resource::<UiPrefabRegistry>();
+            (
+                registry
+                    .find(data.world, LEADERBOARD_ID)
+                    .expect("Couldn't load leaderboard prefab"),
+                registry
+                    .find(data.world, LEADERBOARD_ROW_ID)
+                    .expect("Couldn't load leaderboard row prefab"),
+            )
+        };
+        self.root_entity = Some(data.world.create_entity().with(leaderboard_prefab).build());
+        let entries = data
+            .world
+            .read_resource::<HighScore>()
+            .leaderboard()
+            .to_vec();
+        for _ in &entries {
+            let row = data.world.create_entity().with(row_prefab.clone()).build();
+            self.rows.push(row);
+        }
+        data.data.update(data.world);
+
+        let mut no_entries_label = None;
+        data.world.exec(|ui_finder: UiFinder<'_>| {
+            self.back_button = ui_finder.find(BACK_BUTTON);
+            no_entries_label = ui_finder.find(NO_ENTRIES_LABEL);
+        });
+
+        let mut uitext = data.world.write_storage::<UiText>();
+        let mut transforms = data.world.write_storage::<UiTransform>();
+        let parents = data.world.read_resource::<ParentHierarchy>();
+        if entries.is_empty() {
+            if let Some(text) = no_entries_label.and_then(|label| uitext.get_mut(label)) {
+                text.text = "No runs yet. Get out there!".to_string();
+            }
+        }
+        for (i, (&row, entry)) in self.rows.iter().zip(entries.iter()).enumerate() {
+            if let Some(transform) = transforms.get_mut(row) {
+                transform.local_y = FIRST_ROW_Y - i as f32 * ROW_HEIGHT;
+            }
+            for child in parents.all_children_iter(row) {
+                let value = match transforms.get(child).map(|t| t.id.as_str()) {
+                    Some(RANK_LABEL) => format!("{}.", i + 1),
+                    Some(NAME_LABEL) => entry.name.clone(),
+                    Some(DISTANCE_LABEL) => format!("{:.2} m", entry.get_score()),
+                    // a corrupt save can hold a time that doesn't exist
+                    Some(DATE_LABEL) => Local
+                        .timestamp_opt(entry.timestamp as i64, 0)
+                        .single()
+                        .map_or_else(
+                            || UNKNOWN_DATE.to_string(),
+                            |date| date.format("%Y-%m-%d").to_string(),
+                        ),
+                    _ => continue,
+                };
+                if let Some(text) = uitext.get_mut(child) {
+                    text.text = value;
+                }
+            }
+        }
+    }
+
+    fn deinit_gui(&mut self, data: &mut StateData<GameData>) {
+        for row in self.rows.drain(..) {
+            utils::delete_hierarchy(data.world, row);
+        }
+        if let Some(e) = self.root_entity.take() {
+            utils::delete_hierarchy(data.world, e);
+            self.back_button = None;
+        }
+    }
+}
@@ -1,6 +1,10 @@
 use crate::{
     resources::{
-        audio::initialize_audio, prefabs::initialize_prefabs, sprites::initialize_sprite_sheets,
+        audio::initialize_audio,
+        prefabs::{initialize_gameplay_prefabs, initialize_prefabs},
+        save::load_high_score,
+        sprites::initialize_sprite_sheets,
+        Settings,
     },
     states::MenuState,
 };
@@ -17,9 +21,22 @@ impl SimpleState for LoadingState {
         // Loads Sprites & Prefabs
         // Counters determine time it will take for everything to be loaded
         self.counters.push(initialize_prefabs(&mut data.world));
+        self.counters
+            .push(initialize_gameplay_prefabs(&mut data.world));
         self.counters
             .push(initialize_sprite_sheets(&mut data.world));
         self.counters.push(initialize_audio(&mut data.world));
+
+        // Applies the player's saved controls and volume over the defaults
+        let settings = data
+            .world
+            .entry::<Settings>()
+            .or_insert_with(Settings::load_or_default)
+            .clone();
+        settings.apply(&mut data.world);
+
+        // Restores the high score from previous sessions
+        data.world.insert(load_high_score());
     }
 
     fn update(&mut self, data: &mut StateData<GameData>) -> SimpleTrans {
@@ -37,9 +54,11 @@ impl SimpleState for LoadingState {
             .iter()
             .map(ProgressCounter::num_finished)
             .sum();
-        println!(
+        log::info!(
             "Loading: {}, Failed: {}, Finished: {}",
-            loading, failed, finished,
+            loading,
+            failed,
+            finished,
         );
 
         // checks if there are no more assets that need to be loaded
@@ -1,9 +1,13 @@
 // Creates Game Menu
 
 use crate::{
-    resources::{prefabs::UiPrefabRegistry, ResourceRegistry},
-    states::{CutsceneState, OptionsState},
-    utils::delete_hierarchy,
+    resources::{
+        audio::{play_playlist, MENU_PLAYLIST},
+        prefabs::UiPrefabRegistry,
+        ResourceRegistry,
+    },
+    states::{CutsceneState, LeaderboardState, OptionsState},
+    utils::{confirm_as_click, delete_hierarchy},
 };
 use amethyst::{
     ecs::Entity,
@@ -18,6 +22,7 @@ const MENU_ID: &str = "menu";
 pub struct MenuState {
     start_button: Option<Entity>,
     options_button: Option<Entity>,
+    leaderboard_button: Option<Entity>,
     exit_button: Option<Entity>,
     root_entity: Option<Entity>,
 }
@@ -26,10 +31,10 @@ impl SimpleState for MenuState {
     // handles button presses
     fn handle_event(
         &mut self,
-        _data: StateData<'_, GameData<'_, '_>>,
+        data: StateData<'_, GameData<'_, '_>>,
         event: StateEvent,
     ) -> SimpleTrans {
-        match event {
+        match confirm_as_click(data.world, event, self.start_button) {
             StateEvent::Ui(UiEvent {
                 event_type: UiEventType::Click,
                 target,
@@ -40,6 +45,12 @@ impl SimpleState for MenuState {
                 } else if self.options_button.map_or(false, |button| button == target) {
                     // Options Button: Transition to options screen
                     Trans::Push(Box::new(OptionsState::default()))
+                } else if self
+                    .leaderboard_button
+                    .map_or(false, |button| button == target)
+                {
+                    // Leaderboard Button: Transition to the best runs so far
+                    Trans::Push(Box::new(LeaderboardState::default()))
                 } else if self.exit_button.map_or(false, |button| button == target) {
                     // Exit Button: Quits Game
                     // Quitting game causes error, possible bug?
@@ -76,6 +87,7 @@ impl SimpleState for MenuState {
 
 impl MenuState {
     fn load_ui(&mut self, data: StateData<GameData>) {
+        play_playlist(data.world, MENU_PLAYLIST);
         // Variables that allow menu to be displayed and rendered
         let menu_prefab = data
             .world
@@ -89,6 +101,7 @@ impl MenuState {
         data.world.exec(|ui_finder: UiFinder<'_>| {
             self.start_button = ui_finder.find("start");
             self.options_button = ui_finder.find("options");
+            self.leaderboard_button = ui_finder.find("leaderboard");
             self.exit_button = ui_finder.find("exit");
         });
     }
@@ -98,6 +111,7 @@ impl MenuState {
             delete_hierarchy(&mut data.world, e);
             self.start_button = None;
             self.options_button = None;
+            self.leaderboard_button = None;
             self.exit_button = None;
         }
     }
@@ -3,6 +3,7 @@
 mod cutscene;
 mod gameover;
 mod gameplay;
+mod leaderboard;
 mod loading;
 mod menu;
 mod options;
@@ -12,6 +13,7 @@ mod test;
 pub use cutscene::CutsceneState;
 pub use gameover::GameOverState;
 pub use gameplay::GameplayState;
+pub use leaderboard::LeaderboardState;
 pub use loading::LoadingState;
 pub use menu::MenuState;
 pub use options::OptionsState;
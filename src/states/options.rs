@@ -1,18 +1,47 @@
 use crate::{
-    resources::{prefabs::UiPrefabRegistry, Controls, ResourceRegistry},
-    utils::delete_hierarchy,
+    resources::{
+        prefabs::UiPrefabRegistry, step_volume, Controls, Rebindable, ResourceRegistry, Settings,
+        REBINDABLE,
+    },
+    utils::{confirm_as_click, delete_hierarchy},
 };
 
 use amethyst::{
+    core::transform::ParentHierarchy,
     ecs::Entity,
+    input::{self, Button, InputHandler, StringBindings, VirtualKeyCode},
     prelude::*,
-    ui::{UiEvent, UiEventType, UiFinder, UiText},
+    ui::{UiEvent, UiEventType, UiFinder, UiText, UiTransform},
+    winit::ElementState,
 };
 
 const ROOT_ID: &str = "options";
 const BACK_BUTTON_ID: &str = "back";
 const CONTROLS_BUTTON_ID: &str = "controls";
 const CONTROLS_LABEL_ID: &str = "controls_label";
+const RESET_BUTTON_ID: &str = "reset_controls";
+const STATUS_LABEL_ID: &str = "rebind_status";
+const MUSIC_DOWN_BUTTON_ID: &str = "music_volume_down";
+const MUSIC_UP_BUTTON_ID: &str = "music_volume_up";
+const MUSIC_LABEL_ID: &str = "music_volume_label";
+const SFX_DOWN_BUTTON_ID: &str = "sfx_volume_down";
+const SFX_UP_BUTTON_ID: &str = "sfx_volume_up";
+const SFX_LABEL_ID: &str = "sfx_volume_label";
+
+const REBIND_ROW_ID: &str = "rebind_row";
+const REBIND_BUTTON_ID: &str = "rebind";
+const REBIND_LABEL_ID: &str = "rebind_label";
+
+/// The height of each rebinding row, and where the first one goes
+const REBIND_ROW_HEIGHT: f32 = 50.0;
+const FIRST_REBIND_ROW_Y: f32 = -400.0;
+
+/// The volumes which can be changed in the options
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Volume {
+    Music,
+    Effects,
+}
 
 #[derive(Default)]
 pub struct OptionsState {
@@ -20,6 +49,18 @@ pub struct OptionsState {
     controls_button: Option<Entity>,
     controls_label: Option<Entity>,
     back_button: Option<Entity>,
+    reset_button: Option<Entity>,
+    status_label: Option<Entity>,
+    music_down_button: Option<Entity>,
+    music_up_button: Option<Entity>,
+    music_label: Option<Entity>,
+    sfx_down_button: Option<Entity>,
+    sfx_up_button: Option<Entity>,
+    sfx_label: Option<Entity>,
+    /// One row for each of `REBINDABLE`
+    rebind_rows: Vec<Entity>,
+    /// The key being rebound, while waiting for the player to press a key
+    rebinding: Option<&'static Rebindable>,
 }
 
 impl SimpleState for OptionsState {
@@ -29,7 +70,13 @@ impl SimpleState for OptionsState {
         mut data: StateData<'_, GameData<'_, '_>>,
         event: StateEvent,
     ) -> SimpleTrans {
-        match event {
+        match confirm_as_click(data.world, event, self.back_button) {
+            StateEvent::Window(event) if self.rebinding.is_some() => {
+                if let Some((key, ElementState::Pressed)) = input::get_key(&event) {
+                    self.finish_rebinding(&mut data, key);
+                }
+                Trans::None
+            }
             StateEvent::Ui(UiEvent {
                 event_type: UiEventType::Click,
                 target,
@@ -43,20 +90,52 @@ impl SimpleState for OptionsState {
                     .controls_button
                     .map_or(false, |button| button == target)
                 {
-                    let mut controls = *data
-                        .world
-                        .entry::<Controls>()
-                        .or_insert_with(Default::default);
-                    controls = controls.successor();
-                    controls.set_control_scheme(&mut data.world);
-                    if let Some(label) = self.controls_label {
-                        data.world
-                            .write_storage::<UiText>()
-                            .get_mut(label)
-                            .expect("Couldn't find UiText on Controls Button Label")
-                            .text = controls.get_button_label().to_string();
+                    {
+                        let mut settings = data
+                            .world
+                            .entry::<Settings>()
+                            .or_insert_with(Settings::load_or_default);
+                        settings.controls = settings.controls.successor();
+                        settings.save();
                     }
-                    data.world.insert(controls);
+                    self.apply_controls(&mut data);
+                    Trans::None
+                } else if self.reset_button.map_or(false, |button| button == target) {
+                    {
+                        let mut settings = data
+                            .world
+                            .entry::<Settings>()
+                            .or_insert_with(Settings::load_or_default);
+                        settings.reset_controls();
+                        settings.save();
+                    }
+                    self.apply_controls(&mut data);
+                    self.set_status(&mut data, "Controls reset to default");
+                    Trans::None
+                } else if let Some((volume, steps)) = self.find_volume_change(target) {
+                    {
+                        let mut settings = data.world.write_resource::<Settings>();
+                        match volume {
+                            Volume::Music => {
+                                settings.music_volume = step_volume(settings.music_volume, steps)
+                            }
+                            Volume::Effects => {
+                                settings.sfx_volume = step_volume(settings.sfx_volume, steps)
+                            }
+                        }
+                        settings.save();
+                    }
+                    data.world
+                        .read_resource::<Settings>()
+                        .apply_volume(data.world);
+                    self.update_labels(&mut data);
+                    Trans::None
+                } else if let Some(rebindable) = self.find_rebind_target(&data, target) {
+                    self.rebinding = Some(rebindable);
+                    self.set_status(
+                        &mut data,
+                        &format!("Press a key for {} (Escape to cancel)", rebindable.label),
+                    );
                     Trans::None
                 } else {
                     Trans::None
@@ -78,38 +157,201 @@ impl SimpleState for OptionsState {
 impl OptionsState {
     fn load_ui(&mut self, data: &mut StateData<GameData>) {
         // Instantiate the menu
-        let menu_prefab = data
-            .world
-            .read_resource::<UiPrefabRegistry>()
-            .find(data.world, ROOT_ID)
-            .expect("Couldn't load menu prefab");
+        let (menu_prefab, row_prefab) = {
+            let registry = data.world.read_resource::<UiPrefabRegistry>();
+            (
+                registry
+                    .find(data.world, ROOT_ID)
+                    .expect("Couldn't load menu prefab"),
+                registry
+                    .find(data.world, REBIND_ROW_ID)
+                    .expect("Couldn't load rebinding row prefab"),
+            )
+        };
         self.root_entity = Some(data.world.create_entity().with(menu_prefab).build());
+        for _ in REBINDABLE {
+            let row = data.world.create_entity().with(row_prefab.clone()).build();
+            self.rebind_rows.push(row);
+        }
         data.data.update(&data.world);
         // Assign important entities to member variables
         data.world.exec(|ui_finder: UiFinder<'_>| {
             self.back_button = ui_finder.find(BACK_BUTTON_ID);
             self.controls_button = ui_finder.find(CONTROLS_BUTTON_ID);
             self.controls_label = ui_finder.find(CONTROLS_LABEL_ID);
+            self.reset_button = ui_finder.find(RESET_BUTTON_ID);
+            self.status_label = ui_finder.find(STATUS_LABEL_ID);
+            self.music_down_button = ui_finder.find(MUSIC_DOWN_BUTTON_ID);
+            self.music_up_button = ui_finder.find(MUSIC_UP_BUTTON_ID);
+            self.music_label = ui_finder.find(MUSIC_LABEL_ID);
+            self.sfx_down_button = ui_finder.find(SFX_DOWN_BUTTON_ID);
+            self.sfx_up_button = ui_finder.find(SFX_UP_BUTTON_ID);
+            self.sfx_label = ui_finder.find(SFX_LABEL_ID);
         });
-        let controls = *data
-            .world
-            .entry::<Controls>()
-            .or_insert_with(Default::default);
-        if let Some(label) = self.controls_label {
-            data.world
-                .write_storage::<UiText>()
-                .get_mut(label)
-                .expect("Couldn't find UiText on Controls Button Label")
-                .text = controls.get_button_label().to_string();
+        {
+            let mut transforms = data.world.write_storage::<UiTransform>();
+            for (i, &row) in self.rebind_rows.iter().enumerate() {
+                if let Some(transform) = transforms.get_mut(row) {
+                    transform.local_y = FIRST_REBIND_ROW_Y - i as f32 * REBIND_ROW_HEIGHT;
+                }
+            }
         }
+        data.world
+            .entry::<Settings>()
+            .or_insert_with(Settings::load_or_default);
+        self.update_labels(data);
     }
 
     fn tear_down_ui(&mut self, data: &mut StateData<GameData>) {
+        for row in self.rebind_rows.drain(..) {
+            delete_hierarchy(&mut data.world, row);
+        }
         if let Some(e) = self.root_entity.take() {
             delete_hierarchy(&mut data.world, e);
             self.back_button = None;
             self.controls_button = None;
             self.controls_label = None;
+            self.reset_button = None;
+            self.status_label = None;
+            self.music_down_button = None;
+            self.music_up_button = None;
+            self.music_label = None;
+            self.sfx_down_button = None;
+            self.sfx_up_button = None;
+            self.sfx_label = None;
+        }
+        self.rebinding = None;
+    }
+
+    /// Switch to the controls in the settings, and show them
+    fn apply_controls(&mut self, data: &mut StateData<GameData>) {
+        let settings = data.world.read_resource::<Settings>().clone();
+        settings.apply_controls(&mut data.world);
+        self.update_labels(data);
+    }
+
+    /// Show the volumes, the current control scheme, and the key bound to
+    /// each rebindable action
+    fn update_labels(&mut self, data: &mut StateData<GameData>) {
+        let settings = data.world.read_resource::<Settings>();
+        let controls = settings.controls;
+        let input = data.world.read_resource::<InputHandler<StringBindings>>();
+        let transforms = data.world.read_storage::<UiTransform>();
+        let parents = data.world.read_resource::<ParentHierarchy>();
+        let mut uitext = data.world.write_storage::<UiText>();
+        if let Some(label) = self.controls_label {
+            uitext
+                .get_mut(label)
+                .expect("Couldn't find UiText on Controls Button Label")
+                .text = controls.get_button_label().to_string();
+        }
+        let muted = if settings.muted { " (muted)" } else { "" };
+        for &(label, name, volume) in &[
+            (self.music_label, "Music", settings.music_volume),
+            (self.sfx_label, "Effects", settings.sfx_volume),
+        ] {
+            if let Some(text) = label.and_then(|label| uitext.get_mut(label)) {
+                text.text = format!("{}: {:.0}%{}", name, volume * 100.0, muted);
+            }
+        }
+        for (&row, rebindable) in self.rebind_rows.iter().zip(REBINDABLE) {
+            let label = parents.all_children_iter(row).find(|&child| {
+                transforms
+                    .get(child)
+                    .map_or(false, |transform| transform.id == REBIND_LABEL_ID)
+            });
+            if let Some(text) = label.and_then(|label| uitext.get_mut(label)) {
+                text.text = format!(
+                    "{}: {}",
+                    rebindable.label,
+                    rebindable.button_name(&input.bindings)
+                );
+            }
+        }
+    }
+
+    fn set_status(&mut self, data: &mut StateData<GameData>, status: &str) {
+        if let Some(label) = self.status_label {
+            if let Some(text) = data.world.write_storage::<UiText>().get_mut(label) {
+                text.text = status.to_string();
+            }
+        }
+    }
+
+    /// Find which volume a clicked button changes, and by how many steps
+    fn find_volume_change(&self, target: Entity) -> Option<(Volume, i32)> {
+        [
+            (self.music_down_button, Volume::Music, -1),
+            (self.music_up_button, Volume::Music, 1),
+            (self.sfx_down_button, Volume::Effects, -1),
+            (self.sfx_up_button, Volume::Effects, 1),
+        ]
+        .iter()
+        .find(|(button, _, _)| *button == Some(target))
+        .map(|&(_, volume, steps)| (volume, steps))
+    }
+
+    /// Find which rebindable key a clicked button belongs to
+    fn find_rebind_target(
+        &self,
+        data: &StateData<GameData>,
+        target: Entity,
+    ) -> Option<&'static Rebindable> {
+        let transforms = data.world.read_storage::<UiTransform>();
+        let parents = data.world.read_resource::<ParentHierarchy>();
+        self.rebind_rows
+            .iter()
+            .zip(REBINDABLE)
+            .find(|(row, _)| {
+                parents.all_children_iter(**row).any(|child| {
+                    child == target
+                        && transforms
+                            .get(child)
+                            .map_or(false, |transform| transform.id == REBIND_BUTTON_ID)
+                })
+            })
+            .map(|(_, rebindable)| rebindable)
+    }
+
+    /// Bind the pressed key to whatever is being rebound, unless it is
+    /// already used for something else
+    fn finish_rebinding(&mut self, data: &mut StateData<GameData>, key: VirtualKeyCode) {
+        let rebindable = match self.rebinding.take() {
+            Some(rebindable) => rebindable,
+            None => return,
+        };
+        if key == VirtualKeyCode::Escape {
+            self.set_status(data, "");
+            return;
+        }
+        let button = Button::Key(key);
+        let mut bindings = data
+            .world
+            .read_resource::<InputHandler<StringBindings>>()
+            .bindings
+            .clone();
+        if let Some(other) = rebindable.find_conflict(&bindings, button) {
+            self.set_status(
+                data,
+                &format!("{:?} is already used for {}", key, other.label),
+            );
+            return;
+        }
+        if let Err(e) = rebindable.bind(&mut bindings, button) {
+            log::warn!("Error rebinding {}: {}", rebindable.label, e);
+            self.set_status(data, &format!("Couldn't bind {:?}", key));
+            return;
+        }
+        {
+            let mut settings = data.world.write_resource::<Settings>();
+            settings.controls = Controls::Custom;
+            settings.custom_bindings = Some(bindings);
+            settings.save();
         }
+        self.apply_controls(data);
+        self.set_status(
+            data,
+            &format!("{} is now bound to {:?}", rebindable.label, key),
+        );
     }
 }
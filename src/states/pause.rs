@@ -1,7 +1,7 @@
 use crate::{
-    resources::{prefabs::UiPrefabRegistry, Paused, QuitToMenu, ResourceRegistry},
+    resources::{prefabs::UiPrefabRegistry, Paused, QuitToMenu, ResourceRegistry, PAUSE_ACTION},
     states::OptionsState,
-    utils::delete_hierarchy,
+    utils::{confirm_as_click, delete_hierarchy, is_action_down},
 };
 use amethyst::{
     ecs::Entity,
@@ -54,7 +54,10 @@ impl SimpleState for PauseState {
         data: StateData<'_, GameData<'_, '_>>,
         event: StateEvent,
     ) -> SimpleTrans {
-        match event {
+        if is_action_down(&event, PAUSE_ACTION) {
+            return Trans::Pop;
+        }
+        match confirm_as_click(data.world, event, self.return_to_game_button) {
             StateEvent::Ui(UiEvent {
                 event_type: UiEventType::Click,
                 target,
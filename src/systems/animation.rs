@@ -0,0 +1,82 @@
+// plays each character's animation to match how it is moving
+use crate::{
+    components::{Animation, Facing, Motion, Player},
+    resources::{sprites::SpriteSheetRegister, Paused},
+};
+use amethyst::{
+    assets::AssetStorage,
+    core::{Time, Transform},
+    derive::SystemDesc,
+    ecs::{Join, Read, ReadStorage, System, SystemData, WriteStorage},
+    renderer::{SpriteRender, SpriteSheet},
+};
+
+/// Slower than this, in units per second, counts as standing still
+const IDLE_SPEED: f32 = 20.0;
+
+#[derive(SystemDesc)]
+pub struct AnimationSystem;
+
+impl<'s> System<'s> for AnimationSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        WriteStorage<'s, Animation>,
+        ReadStorage<'s, Facing>,
+        ReadStorage<'s, Player>,
+        ReadStorage<'s, Transform>,
+        WriteStorage<'s, SpriteRender>,
+        Read<'s, SpriteSheetRegister>,
+        Read<'s, AssetStorage<SpriteSheet>>,
+        Read<'s, Paused>,
+        Read<'s, Time>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            mut animations,
+            facings,
+            players,
+            transforms,
+            mut sprite_renders,
+            spritesheet_registry,
+            spritesheet_storage,
+            paused,
+            time,
+        ): Self::SystemData,
+    ) {
+        if *paused == Paused::Paused {
+            return;
+        }
+        let delta = time.delta_seconds();
+        for (animation, facing, player, transform, sprite_render) in (
+            &mut animations,
+            facings.maybe(),
+            players.maybe(),
+            &transforms,
+            &mut sprite_renders,
+        )
+            .join()
+        {
+            let movement = animation.track_position(*transform.translation());
+            let motion = if player.map_or(false, Player::is_airborne) {
+                Motion::Jump
+            } else if delta == 0.0 || movement.norm() / delta < IDLE_SPEED {
+                Motion::Idle
+            } else if movement.x.abs() > movement.y.abs() {
+                Motion::Walk
+            } else {
+                Motion::WalkUp
+            };
+            animation.set_motion(motion);
+            animation.advance(delta);
+
+            let facing = facing.cloned().unwrap_or_default();
+            if let Some(sprite) = animation.sprite(facing).and_then(|(sheet, frame)| {
+                spritesheet_registry.find_sprite_sans_world(&spritesheet_storage, sheet, frame)
+            }) {
+                *sprite_render = sprite;
+            }
+        }
+    }
+}
@@ -0,0 +1,126 @@
+// tracks which obstacles each character is touching
+use crate::{
+    components::{Contacts, Dog, Enemy, Obstacle, ObstacleEffect, Player},
+    resources::{GameEvent, Paused},
+};
+use amethyst::{
+    core::{Time, Transform},
+    derive::SystemDesc,
+    ecs::{Entities, Entity, Join, Read, ReadStorage, System, SystemData, Write, WriteStorage},
+    shrev::EventChannel,
+};
+use nalgebra::base::Vector3;
+
+#[derive(SystemDesc)]
+pub struct ObstacleContactSystem;
+
+impl<'s> System<'s> for ObstacleContactSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        WriteStorage<'s, Transform>,
+        ReadStorage<'s, Obstacle>,
+        WriteStorage<'s, Contacts>,
+        ReadStorage<'s, Player>,
+        ReadStorage<'s, Enemy>,
+        ReadStorage<'s, Dog>,
+        Read<'s, Paused>,
+        Read<'s, Time>,
+        Write<'s, EventChannel<GameEvent>>,
+        Entities<'s>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            mut transforms,
+            obstacles,
+            mut contacts,
+            players,
+            enemies,
+            dogs,
+            paused,
+            time,
+            mut events,
+            entities,
+        ): Self::SystemData,
+    ) {
+        if *paused == Paused::Paused {
+            return;
+        }
+        // jumping players clear everything but blocking obstacles like cars,
+        // players hiding in a car touch nothing at all, and the enemy can't
+        // do either
+        let characters: Vec<(Entity, Vector3<f32>, bool, bool)> =
+            (&entities, &players, &transforms)
+                .join()
+                .map(|(entity, player, transform)| {
+                    (
+                        entity,
+                        *transform.translation(),
+                        player.is_airborne(),
+                        player.is_hidden(),
+                    )
+                })
+                .chain(
+                    (&entities, &enemies, &transforms)
+                        .join()
+                        .map(|(entity, _, transform)| {
+                            (entity, *transform.translation(), false, false)
+                        }),
+                )
+                .collect();
+
+        for (character, position, airborne, hidden) in characters {
+            let mut touching = Vec::new();
+            let mut knockback = Vector3::new(0.0, 0.0, 0.0f32);
+            // hidden characters touch nothing, though their old contacts
+            // still end below
+            if !hidden {
+                for (obstacle_entity, obstacle, obstacle_transform) in
+                    (&entities, &obstacles, &transforms).join()
+                {
+                    if airborne && !obstacle.has_effect(ObstacleEffect::Block) {
+                        continue;
+                    }
+                    // dogs busy with something the player threw ignore everyone
+                    if dogs.get(obstacle_entity).map_or(false, Dog::is_distracted) {
+                        continue;
+                    }
+                    // keeps track of distance between obstacle and character
+                    let offset = position - obstacle_transform.translation();
+                    if obstacle.contains(offset.x, offset.y) {
+                        touching.push(obstacle_entity);
+                        if let Some(speed) = obstacle.knockback() {
+                            if offset.norm_squared() != 0.0 {
+                                knockback += offset.normalize() * speed;
+                            }
+                        }
+                    }
+                }
+            }
+
+            let character_contacts = contacts
+                .entry(character)
+                .expect("Tried to track contacts of a dead entity")
+                .or_insert_with(Default::default);
+            character_contacts.update(touching);
+            // announce each new contact once, rather than every frame of it
+            for &obstacle in character_contacts.entered() {
+                if let Some(kind) = obstacles.get(obstacle).map(|o| o.kind.clone()) {
+                    events.single_write(GameEvent::ObstacleContact {
+                        character,
+                        obstacle,
+                        kind,
+                    });
+                }
+            }
+
+            // push the character away from any knockback obstacles
+            if knockback.norm_squared() != 0.0 {
+                if let Some(transform) = transforms.get_mut(character) {
+                    transform.prepend_translation(knockback * time.delta_seconds());
+                }
+            }
+        }
+    }
+}
@@ -1,52 +1,136 @@
 use crate::{
-    components::{Dog, Enemy, Mud, Player},
-    resources::{CollisionEvent, Paused},
+    components::{
+        Contacts, Enemy, EnemyState, Facing, Obstacle, Player, StatusEffects, StatusKind, Steering,
+        Vision,
+    },
+    resources::{ChaseDistance, CollisionEvent, GameEvent, Paused},
 };
 use amethyst::{
-    core::Transform,
+    core::{Time, Transform},
     derive::SystemDesc,
-    ecs::{Join, Read, ReadStorage, System, SystemData, Write, WriteStorage},
+    ecs::{Entities, Join, Read, ReadStorage, System, SystemData, Write, WriteStorage},
 };
 use shrev::EventChannel;
 
+use super::status::apply_contact_effects;
+
 #[derive(SystemDesc)]
 pub struct EnemyMovementSystem;
 
 impl<'s> System<'s> for EnemyMovementSystem {
+    #[allow(clippy::type_complexity)]
     type SystemData = (
         WriteStorage<'s, Transform>,
         ReadStorage<'s, Player>,
-        ReadStorage<'s, Enemy>,
+        WriteStorage<'s, Enemy>,
+        ReadStorage<'s, Steering>,
+        ReadStorage<'s, Vision>,
+        ReadStorage<'s, StatusEffects>,
+        ReadStorage<'s, Obstacle>,
         Read<'s, Paused>,
+        Read<'s, Time>,
+        Write<'s, ChaseDistance>,
+        WriteStorage<'s, Facing>,
     );
 
-    fn run(&mut self, (mut transforms, players, enemies, paused): Self::SystemData) {
+    fn run(
+        &mut self,
+        (
+            mut transforms,
+            players,
+            mut enemies,
+            steerings,
+            visions,
+            status_effects,
+            obstacles,
+            paused,
+            time,
+            mut chase_distance,
+            mut facings,
+        ): Self::SystemData,
+    ) {
         if *paused == Paused::Paused {
             return;
         }
-        if let Some(player_position) = (&players, &transforms)
+        let delta = time.delta_seconds();
+        let mut closest: Option<f32> = None;
+        if let Some((player_position, hidden)) = (&players, &transforms)
             .join()
             .next()
-            .map(|(_, t)| *t.translation())
+            .map(|(player, t)| (*t.translation(), player.is_hidden()))
         {
-            for (enemy, transform) in (&enemies, &mut transforms).join() {
-                let movement = player_position - transform.translation();
-                if movement.norm_squared() != 0.0 {
-                    transform.prepend_translation(
-                        movement.normalize()
-                            * enemy.speed((player_position - transform.translation()).norm()),
+            let obstacle_positions = (&obstacles, &transforms)
+                .join()
+                .map(|(obstacle, transform)| (obstacle, *transform.translation()))
+                .collect::<Vec<_>>();
+            for (enemy, steering, vision, effects, transform, facing) in (
+                &mut enemies,
+                steerings.maybe(),
+                visions.maybe(),
+                status_effects.maybe(),
+                &mut transforms,
+                facings.maybe(),
+            )
+                .join()
+            {
+                let position = *transform.translation();
+                // a player hiding in a car can't be seen or heard, and one
+                // behind a car or out of view can only be heard
+                let heard = if hidden { None } else { Some(player_position) };
+                let seen = heard.filter(|player_position| {
+                    vision.map_or(true, |vision| {
+                        vision.can_see(
+                            &position,
+                            &enemy.heading(),
+                            player_position,
+                            &obstacle_positions,
+                        )
+                    })
+                });
+                let stunned = effects.map_or(false, |effects| effects.has(StatusKind::Stun));
+                enemy.think(seen, heard, stunned, delta);
+
+                let target = match enemy.target(&position, delta) {
+                    Some(target) => target,
+                    None => continue,
+                };
+                let player_distance = (player_position - position).norm();
+                if enemy.state() == EnemyState::Chase {
+                    closest = Some(
+                        closest.map_or(player_distance, |closest| closest.min(player_distance)),
                     );
                 }
+                let speed = enemy.move_speed(player_distance);
+
+                let movement = target - position;
+                let distance = movement.norm();
+                if distance == 0.0 {
+                    continue;
+                }
+                // steers around obstacles in the way, if it knows how to
+                let direction = match steering {
+                    Some(steering) => steering.steer(&position, &target, &obstacle_positions),
+                    None => movement / distance,
+                };
+                transform.prepend_translation(direction * (speed * delta).min(distance));
+                enemy.look(&direction);
+                if let Some(facing) = facing {
+                    facing.turn_towards(&direction);
+                }
             }
         } else if (&enemies,).join().next().is_some() {
             log::warn!("No players found to pursue");
         }
+        *chase_distance = ChaseDistance(closest);
     }
 }
 
 const COLLISION_RADIUS: f32 = 9.0;
-#[derive(SystemDesc)]
-pub struct EnemyCollisionSystem;
+#[derive(Default)]
+pub struct EnemyCollisionSystem {
+    /// Whether the enemy was already touching the player last frame
+    caught: bool,
+}
 
 impl<'s> System<'s> for EnemyCollisionSystem {
     #[allow(clippy::type_complexity)]
@@ -56,17 +140,22 @@ impl<'s> System<'s> for EnemyCollisionSystem {
         ReadStorage<'s, Enemy>,
         Read<'s, Paused>,
         Write<'s, EventChannel<CollisionEvent>>,
+        Write<'s, EventChannel<GameEvent>>,
     );
 
     fn run(
         &mut self,
-        (transforms, players, enemies, paused, mut collision_channel): Self::SystemData,
+        (transforms, players, enemies, paused, mut collision_channel, mut events): Self::SystemData,
     ) {
         if *paused == Paused::Paused {
             return;
         }
-        for ((_, player_transform), (_, enemy_transform)) in
-            (&players, &transforms).join().flat_map(|p| {
+        let mut caught = false;
+        // a player hiding in a car can't be caught
+        for ((_, player_transform), (_, enemy_transform)) in (&players, &transforms)
+            .join()
+            .filter(|(player, _)| !player.is_hidden())
+            .flat_map(|p| {
                 (&enemies, &transforms)
                     .join()
                     .map(|e| (p, e))
@@ -78,8 +167,14 @@ impl<'s> System<'s> for EnemyCollisionSystem {
             {
                 log::info!("Collision between player and enemy");
                 collision_channel.single_write(CollisionEvent);
+                caught = true;
             }
         }
+        // only announce the moment of being caught
+        if caught && !self.caught {
+            events.single_write(GameEvent::Caught);
+        }
+        self.caught = caught;
     }
 }
 
@@ -88,40 +183,33 @@ pub struct EnemyObjectCollisionSystem;
 
 impl<'s> System<'s> for EnemyObjectCollisionSystem {
     type SystemData = (
-        WriteStorage<'s, Enemy>,
-        ReadStorage<'s, Dog>,
-        ReadStorage<'s, Mud>,
+        ReadStorage<'s, Enemy>,
+        ReadStorage<'s, Contacts>,
+        ReadStorage<'s, Obstacle>,
+        WriteStorage<'s, StatusEffects>,
         Read<'s, Paused>,
+        Entities<'s>,
     );
 
-    fn run(&mut self, (mut enemies, dogs, muds, paused): Self::SystemData) {
+    fn run(
+        &mut self,
+        (enemies, contacts, obstacles, mut status_effects, paused, entities): Self::SystemData,
+    ) {
         if *paused == Paused::Paused {
             return;
         }
-        for (enemy,) in (&mut enemies,).join() {
-            // checks if enemy is hit by dog
-            let mut hit_by_dog: bool = false;
-            for (dog,) in (&dogs,).join() {
-                if dog.is_enemy_touching {
-                    hit_by_dog = true;
-                }
-            }
-
-            // checks if enemy is hit by mud
-            let mut hit_by_mud: bool = false;
-            for (mud,) in (&muds,).join() {
-                if mud.is_enemy_touching {
-                    hit_by_mud = true;
-                }
-            }
-
-            // adjust enemy's speed bacsed on their collisions
-            if hit_by_dog {
-                enemy.stop();
-            } else if hit_by_mud {
-                enemy.slow_down();
-            } else {
-                enemy.normal_speed();
+        for (entity, _, contacts) in (&entities, &enemies, &contacts).join() {
+            let effects = status_effects
+                .entry(entity)
+                .expect("Tried to affect a dead entity")
+                .or_insert_with(Default::default);
+            // applies the effects of every obstacle this enemy is touching
+            for obstacle in contacts
+                .touching()
+                .iter()
+                .filter_map(|&obstacle| obstacles.get(obstacle))
+            {
+                apply_contact_effects(effects, obstacle);
             }
         }
     }
@@ -0,0 +1,133 @@
+// lets the player hide from the enemy in parked cars
+use crate::{
+    components::{Obstacle, ObstacleEffect, Player},
+    resources::{Paused, ENTER_CAR_ACTION},
+};
+use amethyst::{
+    core::{Hidden, Time, Transform},
+    ecs::{Entities, Entity, Join, Read, ReadStorage, System, SystemData, WriteStorage},
+    input::{InputHandler, StringBindings},
+};
+use nalgebra::base::Vector3;
+
+use super::player::AREA_WIDTH;
+
+/// How close to a parked car the player has to be to get in
+const HIDE_REACH: f32 = 40.0;
+/// How far from the side of the car the player gets out
+const EXIT_MARGIN: f32 = 10.0;
+
+/// Hides the player in a parked car when the enter car action is pressed
+/// next to one, and gets them out again when it is pressed again or they
+/// run out of time
+#[derive(Default)]
+pub struct HideSystem {
+    /// Whether the enter car action was held last frame, so that holding
+    /// it down doesn't get the player straight back out
+    held: bool,
+}
+
+impl<'s> System<'s> for HideSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        WriteStorage<'s, Player>,
+        WriteStorage<'s, Transform>,
+        ReadStorage<'s, Obstacle>,
+        WriteStorage<'s, Hidden>,
+        Read<'s, InputHandler<StringBindings>>,
+        Read<'s, Paused>,
+        Read<'s, Time>,
+        Entities<'s>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            mut players,
+            mut transforms,
+            obstacles,
+            mut hiddens,
+            input,
+            paused,
+            time,
+            entities,
+        ): Self::SystemData,
+    ) {
+        if *paused == Paused::Paused {
+            return;
+        }
+        let held = input.action_is_down(ENTER_CAR_ACTION).unwrap_or(false);
+        let pressed = held && !self.held;
+        self.held = held;
+
+        // parked cars and the like, which the player can hide in
+        let cars = (&entities, &obstacles, &transforms)
+            .join()
+            .filter(|(_, obstacle, _)| obstacle.has_effect(ObstacleEffect::Block))
+            .map(|(entity, obstacle, transform)| (entity, obstacle, *transform.translation()))
+            .collect::<Vec<_>>();
+
+        for (entity, player, transform) in (&entities, &mut players, &mut transforms).join() {
+            let out_of_time = player.tick_hiding(time.delta_seconds());
+            if let Some(car) = player.hiding_in() {
+                let car_gone = !entities.is_alive(car);
+                if !(pressed || out_of_time || car_gone) {
+                    continue;
+                }
+                player.leave_car();
+                hiddens.remove(entity);
+                log::info!("Player got out of a car");
+                if let Some(exit) = exit_position(car, *transform.translation(), &cars) {
+                    transform.translation_mut().x = exit.x;
+                    transform.translation_mut().y = exit.y;
+                }
+            } else if pressed {
+                let position = *transform.translation();
+                let nearest = cars
+                    .iter()
+                    .map(|(car, obstacle, car_position)| {
+                        let offset = position - car_position;
+                        (*car, obstacle.distance_outside(offset.x, offset.y))
+                    })
+                    .filter(|&(_, distance)| distance.is_finite() && distance <= HIDE_REACH)
+                    .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+                if let Some((car, _)) = nearest {
+                    if player.hide_in(car) {
+                        log::info!("Player hid in a car");
+                        hiddens.insert(entity, Hidden).expect("Error hiding player");
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Where the player at `position` should get out of `car`: the nearest
+/// side which is in the street and not blocked by another car. Returns
+/// `None` if the car is gone, leaving the player where they are.
+fn exit_position(
+    car: Entity,
+    position: Vector3<f32>,
+    cars: &[(Entity, &Obstacle, Vector3<f32>)],
+) -> Option<Vector3<f32>> {
+    let (_, obstacle, car_position) = cars.iter().find(|(other, _, _)| *other == car)?;
+    let offset = position - car_position;
+    let exits = obstacle
+        .exits(offset.x, offset.y, EXIT_MARGIN)
+        .into_iter()
+        .map(|(x, y)| Vector3::new(car_position.x + x, car_position.y + y, position.z))
+        .collect::<Vec<_>>();
+    let is_free = |exit: &Vector3<f32>| {
+        exit.x.abs() <= AREA_WIDTH
+            && cars.iter().all(|(other, obstacle, other_position)| {
+                let offset = exit - other_position;
+                *other == car || !obstacle.contains(offset.x, offset.y)
+            })
+    };
+    // if every side is blocked, squeeze out the nearest one anyway
+    exits
+        .iter()
+        .find(|exit| is_free(exit))
+        .or_else(|| exits.first())
+        .cloned()
+}
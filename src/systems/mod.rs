@@ -1,17 +1,143 @@
+mod animation;
 mod background;
-mod car;
+mod contact;
 mod dog;
 mod enemy;
-mod mud;
+mod hiding;
+mod music;
 mod obstacles;
+mod pickup;
 mod player;
+mod projectile;
 mod score_tracking;
+mod sound;
+mod status;
+mod traffic;
 
+pub use animation::AnimationSystem;
 pub use background::BackgroundRepeatSystem;
-pub use car::CarSystem;
-pub use dog::{DogCollisionSystem, DogSystem};
+pub use contact::ObstacleContactSystem;
+pub use dog::DogSystem;
 pub use enemy::{EnemyCollisionSystem, EnemyMovementSystem, EnemyObjectCollisionSystem};
-pub use mud::MudSystem;
+pub use hiding::HideSystem;
+pub use music::AdaptiveMusicSystem;
 pub use obstacles::ObstacleRandomizationSystem;
-pub use player::{PlayerCollisionSystem, PlayerSystem};
+pub use pickup::{PickupSystem, SkateboardSystem};
+pub use player::{JumpSystem, PlayerCollisionSystem, PlayerSystem};
+pub use projectile::{AmmoDisplaySystem, ProjectileSystem, ThrowSystem};
 pub use score_tracking::ScoreTrackingSystem;
+pub use sound::{MuteSystem, MuteSystemDesc, SoundEffectSystem, SoundEffectSystemDesc};
+pub use status::StatusEffectSystem;
+pub use traffic::TrafficSystem;
+
+use crate::resources::prefabs::{CharacterPrefab, ObstaclePrefab};
+use amethyst::{assets::PrefabLoaderSystemDesc, prelude::*};
+
+/// Register the prefab loaders and systems that make up the gameplay
+/// itself. Both the game and the headless simulation build their
+/// dispatcher from this, so it must not depend on rendering or audio.
+/// Expects an `InputBundle` to already be registered.
+pub fn with_gameplay_systems<'a, 'b>(builder: GameDataBuilder<'a, 'b>) -> GameDataBuilder<'a, 'b> {
+    builder
+        .with_system_desc(
+            PrefabLoaderSystemDesc::<CharacterPrefab>::default(),
+            "character_prefab_loader",
+            &[],
+        )
+        .with_system_desc(
+            PrefabLoaderSystemDesc::<ObstaclePrefab>::default(),
+            "obstacle_prefab_loader",
+            &[],
+        )
+        .with(PlayerSystem, "player_system", &["input_system"])
+        .with(JumpSystem::default(), "jump_system", &["input_system"])
+        .with(
+            HideSystem::default(),
+            "hide_system",
+            &["input_system", "player_system"],
+        )
+        .with(ScoreTrackingSystem, "score_system", &["player_system"])
+        .with(
+            ThrowSystem::default(),
+            "throw_system",
+            &["input_system", "player_system"],
+        )
+        .with(
+            AmmoDisplaySystem::default(),
+            "ammo_display_system",
+            &["throw_system"],
+        )
+        .with(
+            ObstacleRandomizationSystem,
+            "obstacle_randomization_system",
+            &["player_system"],
+        )
+        .with(
+            TrafficSystem,
+            "traffic_system",
+            &["obstacle_randomization_system"],
+        )
+        .with(
+            EnemyMovementSystem,
+            "enemy_movement_system",
+            &["player_system", "hide_system"],
+        )
+        .with(
+            EnemyCollisionSystem::default(),
+            "enemy_collision_system",
+            &["player_system", "enemy_movement_system"],
+        )
+        .with(
+            ObstacleContactSystem,
+            "obstacle_contact_system",
+            &[
+                "player_system",
+                "jump_system",
+                "hide_system",
+                "traffic_system",
+                "enemy_movement_system",
+            ],
+        )
+        .with(
+            EnemyObjectCollisionSystem,
+            "enemy_object_collision_system",
+            &["enemy_movement_system", "obstacle_contact_system"],
+        )
+        .with(DogSystem, "dog_system", &["player_system"])
+        .with(
+            ProjectileSystem,
+            "projectile_system",
+            &["throw_system", "enemy_movement_system", "dog_system"],
+        )
+        .with(PickupSystem, "pickup_system", &["obstacle_contact_system"])
+        .with(
+            PlayerCollisionSystem,
+            "player_col_system",
+            &["player_system", "pickup_system"],
+        )
+        .with(
+            StatusEffectSystem,
+            "status_effect_system",
+            &[
+                "player_col_system",
+                "enemy_object_collision_system",
+                "projectile_system",
+            ],
+        )
+        .with(
+            SkateboardSystem,
+            "skateboard_system",
+            &["status_effect_system", "hide_system"],
+        )
+        .with(
+            AnimationSystem,
+            "animation_system",
+            &[
+                "player_system",
+                "jump_system",
+                "enemy_movement_system",
+                "dog_system",
+                "traffic_system",
+            ],
+        )
+}
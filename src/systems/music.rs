@@ -0,0 +1,120 @@
+// fades the music to match how close the chase is
+use crate::resources::{audio::ChaseMusic, ChaseDistance, Paused, Settings};
+use amethyst::{
+    assets::AssetStorage,
+    audio::{output::Output, AudioSink, Source},
+    core::Time,
+    ecs::{Read, System, Write},
+};
+
+/// How long it takes to fade fully from one layer of music to another
+const CROSSFADE_SECONDS: f32 = 1.5;
+/// How loud the music is while the game is paused
+const DUCKED_MIX: f32 = 0.3;
+/// How long it takes to duck the music, or bring it back up
+const DUCK_SECONDS: f32 = 0.25;
+
+/// Move `value` towards `target` by no more than `step`
+fn approach(value: f32, target: f32, step: f32) -> f32 {
+    if value < target {
+        (value + step).min(target)
+    } else {
+        (value - step).max(target)
+    }
+}
+
+/// Crossfades from the playlist to the chase stem matching how close the
+/// enemy is, and ducks all music while the game is paused
+pub struct AdaptiveMusicSystem {
+    /// How loud the music is relative to the music volume, from 0 to 1
+    duck_mix: f32,
+}
+
+impl Default for AdaptiveMusicSystem {
+    fn default() -> Self {
+        AdaptiveMusicSystem { duck_mix: 1.0 }
+    }
+}
+
+impl<'s> System<'s> for AdaptiveMusicSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Read<'s, ChaseDistance>,
+        Read<'s, Paused>,
+        Read<'s, Settings>,
+        Read<'s, Time>,
+        Read<'s, AssetStorage<Source>>,
+        Option<Read<'s, Output>>,
+        Option<Write<'s, AudioSink>>,
+        Write<'s, ChaseMusic>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            chase_distance,
+            paused,
+            settings,
+            time,
+            sources,
+            output,
+            sink,
+            mut chase,
+        ): Self::SystemData,
+    ) {
+        // fades keep going while paused, so real time is used
+        let delta = time.delta_real_seconds();
+        let duck_target = if *paused == Paused::Paused {
+            DUCKED_MIX
+        } else {
+            1.0
+        };
+        self.duck_mix = approach(self.duck_mix, duck_target, delta / DUCK_SECONDS);
+        let volume = settings.music_output_volume() * self.duck_mix;
+
+        let target = if chase.layers.is_empty() {
+            None
+        } else {
+            chase_distance.0.map(|distance| chase.intensity(distance))
+        };
+        let fade = delta / CROSSFADE_SECONDS;
+
+        // restart the stems together at the start of every chase, so that
+        // they stay in time with each other
+        let silent = chase.layers.iter().all(|layer| layer.mix == 0.0);
+        if let (Some(output), Some(_), true) = (&output, target, silent) {
+            for layer in &mut chase.layers {
+                if let Some(old_sink) = layer.sink.take() {
+                    old_sink.stop();
+                }
+                layer.sink = Some(AudioSink::new(output));
+            }
+        }
+
+        for layer in &mut chase.layers {
+            let layer_target = if Some(layer.intensity) == target {
+                1.0
+            } else {
+                0.0
+            };
+            layer.mix = approach(layer.mix, layer_target, fade);
+            if let Some(layer_sink) = &mut layer.sink {
+                // stems loop for as long as the chase lasts
+                if layer_sink.empty() && target.is_some() {
+                    if let Some(source) = sources.get(&layer.track) {
+                        if let Err(e) = layer_sink.append(source) {
+                            log::warn!("Couldn't play chase stem: {}", e);
+                        }
+                    }
+                }
+                layer_sink.set_volume(volume * layer.mix);
+            }
+        }
+
+        let playlist_target = if target.is_some() { 0.0 } else { 1.0 };
+        chase.playlist_mix = approach(chase.playlist_mix, playlist_target, fade);
+        if let Some(mut sink) = sink {
+            sink.set_volume(volume * chase.playlist_mix);
+        }
+    }
+}
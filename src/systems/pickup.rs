@@ -0,0 +1,84 @@
+// lets the player pick up skateboards lying in the street
+use crate::{
+    components::{Contacts, Obstacle, Player, Skateboard, StatusEffect, StatusEffects, StatusKind},
+    resources::Paused,
+};
+use amethyst::{
+    core::{transform::Parent, Hidden},
+    derive::SystemDesc,
+    ecs::{Entities, Join, Read, ReadStorage, System, SystemData, WriteStorage},
+};
+
+#[derive(SystemDesc)]
+pub struct PickupSystem;
+
+impl<'s> System<'s> for PickupSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        ReadStorage<'s, Player>,
+        ReadStorage<'s, Contacts>,
+        ReadStorage<'s, Obstacle>,
+        WriteStorage<'s, StatusEffects>,
+        Read<'s, Paused>,
+        Entities<'s>,
+    );
+
+    fn run(
+        &mut self,
+        (players, contacts, obstacles, mut status_effects, paused, entities): Self::SystemData,
+    ) {
+        if *paused == Paused::Paused {
+            return;
+        }
+        for (entity, _, contacts) in (&entities, &players, &contacts).join() {
+            for &obstacle_entity in contacts.entered() {
+                if let Some(seconds) = obstacles.get(obstacle_entity).and_then(Obstacle::boost) {
+                    log::info!("Player picked up a skateboard");
+                    status_effects
+                        .entry(entity)
+                        .expect("Tried to affect a dead entity")
+                        .or_insert_with(Default::default)
+                        .apply(StatusEffect::boost(seconds));
+                    entities
+                        .delete(obstacle_entity)
+                        .expect("Error removing pickup");
+                }
+            }
+        }
+    }
+}
+
+/// Shows the skateboard under the player only while they are riding it,
+/// and not hiding in a car
+#[derive(SystemDesc)]
+pub struct SkateboardSystem;
+
+impl<'s> System<'s> for SkateboardSystem {
+    type SystemData = (
+        ReadStorage<'s, Skateboard>,
+        ReadStorage<'s, Parent>,
+        ReadStorage<'s, StatusEffects>,
+        ReadStorage<'s, Player>,
+        WriteStorage<'s, Hidden>,
+        Entities<'s>,
+    );
+
+    fn run(
+        &mut self,
+        (skateboards, parents, status_effects, players, mut hiddens, entities): Self::SystemData,
+    ) {
+        for (skateboard, _, parent) in (&entities, &skateboards, &parents).join() {
+            let riding = status_effects
+                .get(parent.entity)
+                .map_or(false, |effects| effects.has(StatusKind::Boost))
+                && !players.get(parent.entity).map_or(false, Player::is_hidden);
+            if riding {
+                hiddens.remove(skateboard);
+            } else if !hiddens.contains(skateboard) {
+                hiddens
+                    .insert(skateboard, Hidden)
+                    .expect("Error hiding skateboard");
+            }
+        }
+    }
+}
@@ -1,16 +1,22 @@
 use crate::{
-    components::{Car, Dog, Mud, Player},
-    resources::Paused,
+    components::{Contacts, Facing, Obstacle, ObstacleEffect, Player, StatusEffects, StatusKind},
+    resources::{Paused, JUMP_ACTION},
 };
 use amethyst::{
-    core::Transform,
+    core::{Time, Transform},
     derive::SystemDesc,
-    ecs::{Join, Read, ReadStorage, System, SystemData, WriteStorage},
+    ecs::{Entities, Join, Read, ReadStorage, System, SystemData, WriteStorage},
     input::{InputHandler, StringBindings},
 };
 use nalgebra::base::Vector3;
 
-const AREA_WIDTH: f32 = 760.0f32;
+use super::status::apply_contact_effects;
+
+pub(super) const AREA_WIDTH: f32 = 760.0f32;
+
+/// How far outside a blocking obstacle the player gets put when they walk
+/// into it
+const SHOVE_MARGIN: f32 = 0.01;
 
 #[derive(SystemDesc)]
 pub struct PlayerSystem;
@@ -22,51 +28,118 @@ impl<'s> System<'s> for PlayerSystem {
         ReadStorage<'s, Player>,
         Read<'s, InputHandler<StringBindings>>,
         Read<'s, Paused>,
-        ReadStorage<'s, Car>,
+        ReadStorage<'s, Obstacle>,
+        ReadStorage<'s, Contacts>,
+        Read<'s, Time>,
+        Entities<'s>,
+        WriteStorage<'s, Facing>,
     );
 
-    fn run(&mut self, (mut transforms, players, input, paused, cars): Self::SystemData) {
+    fn run(
+        &mut self,
+        (
+            mut transforms,
+            players,
+            input,
+            paused,
+            obstacles,
+            contacts,
+            time,
+            entities,
+            mut facings,
+        ): Self::SystemData,
+    ) {
         if *paused == Paused::Paused {
             return;
         }
-        let mut movement: Vector3<f32> = Vector3::new(0.0, 0.0, 0.0);
-        for (player, transform) in (&players, &mut transforms).join() {
-            // unwraps elements from inputs.ron
-            let horizontal = input.axis_value("horizontal").unwrap_or(0.0);
-            let vertical = input.axis_value("vertical").unwrap_or(0.0);
+        // unwraps elements from inputs.ron, letting the d-pad work with any controls
+        let axis = |name: &str| input.axis_value(name).unwrap_or(0.0);
+        let horizontal = axis("horizontal") + axis("dpad_horizontal");
+        let vertical = axis("vertical") + axis("dpad_vertical");
+        let mut movement: Vector3<f32> = Vector3::new(horizontal, vertical, 0.0f32);
+        // a half-tilted stick moves slower, but diagonals aren't faster
+        if movement.norm_squared() > 1.0 {
+            movement.normalize_mut();
+        }
 
+        for (player, transform, facing) in (&players, &mut transforms, facings.maybe()).join() {
+            // players hiding in a car stay put until they get out
+            if player.is_hidden() {
+                continue;
+            }
             // lets player move
-            movement = Vector3::new(horizontal, vertical, 0.0f32);
             if movement.norm_squared() != 0.0 {
-                transform.prepend_translation(movement.normalize() * (player.speed));
+                transform.prepend_translation(
+                    movement * player.effective_speed() * time.delta_seconds(),
+                );
+                if let Some(facing) = facing {
+                    facing.turn_towards(&movement);
+                }
             }
             // clamp to area boundaries
             transform.translation_mut().x =
                 transform.translation().x.max(-AREA_WIDTH).min(AREA_WIDTH);
         }
-        // finds car area & location of car impact
-        let mut car_x_min = 0.0f32;
-        let mut car_x_max = 0.0f32;
-        let mut car_y_min = 0.0f32;
-        let mut car_y_max = 0.0f32;
-        for (car, transform) in (&cars, &transforms).join() {
-            if car.is_player_touching {
-                car_x_min = transform.translation().x - car.width;
-                car_x_max = transform.translation().x + car.width;
-                car_y_min = transform.translation().y - car.height;
-                car_y_max = transform.translation().y + car.height;
+        // finds the blocking obstacle each player ran into
+        let blocked = (&entities, &players, &contacts)
+            .join()
+            .filter_map(|(player, _, contacts)| {
+                contacts
+                    .touching()
+                    .iter()
+                    .filter_map(|&obstacle_entity| {
+                        let obstacle = obstacles.get(obstacle_entity)?;
+                        if !obstacle.has_effect(ObstacleEffect::Block) {
+                            return None;
+                        }
+                        Some((obstacle, *transforms.get(obstacle_entity)?.translation()))
+                    })
+                    .last()
+                    .map(|blocker| (player, blocker))
+            })
+            .collect::<Vec<_>>();
+
+        // If inside a blocking obstacle like a car, move the player out the nearest side
+        for (player, (obstacle, obstacle_position)) in blocked {
+            if let Some(transform) = transforms.get_mut(player) {
+                let offset = transform.translation() - obstacle_position;
+                if obstacle.contains(offset.x, offset.y) {
+                    let (x, y) = obstacle.exits(offset.x, offset.y, SHOVE_MARGIN)[0];
+                    transform.translation_mut().x = obstacle_position.x + x;
+                    transform.translation_mut().y = obstacle_position.y + y;
+                }
             }
         }
-        if movement.norm_squared() > 0.0 {
-            // If inside a car, move the player to outside the car
-            for (_, transform) in (&players, &mut transforms).join() {
-                while transform.translation().x > car_x_min
-                    && transform.translation().x < car_x_max
-                    && transform.translation().y > car_y_min
-                    && transform.translation().y < car_y_max
-                {
-                    transform.prepend_translation(-movement.normalize() * 0.01);
-                }
+    }
+}
+
+/// Makes the player jump when the jump action is pressed
+#[derive(Default)]
+pub struct JumpSystem {
+    /// Whether the jump action was held last frame, so that holding it
+    /// down only jumps once
+    held: bool,
+}
+
+impl<'s> System<'s> for JumpSystem {
+    type SystemData = (
+        WriteStorage<'s, Player>,
+        Read<'s, InputHandler<StringBindings>>,
+        Read<'s, Paused>,
+        Read<'s, Time>,
+    );
+
+    fn run(&mut self, (mut players, input, paused, time): Self::SystemData) {
+        if *paused == Paused::Paused {
+            return;
+        }
+        let held = input.action_is_down(JUMP_ACTION).unwrap_or(false);
+        let pressed = held && !self.held;
+        self.held = held;
+        for player in (&mut players).join() {
+            player.tick_jump(time.delta_seconds());
+            if pressed && player.jump() {
+                log::info!("Player jumped");
             }
         }
     }
@@ -78,55 +151,42 @@ pub struct PlayerCollisionSystem;
 impl<'s> System<'s> for PlayerCollisionSystem {
     #[allow(clippy::type_complexity)]
     type SystemData = (
-        WriteStorage<'s, Player>,
-        ReadStorage<'s, Car>,
-        ReadStorage<'s, Dog>,
-        ReadStorage<'s, Mud>,
+        ReadStorage<'s, Player>,
+        ReadStorage<'s, Contacts>,
+        ReadStorage<'s, Obstacle>,
+        WriteStorage<'s, StatusEffects>,
         Read<'s, Paused>,
+        Entities<'s>,
     );
 
-    fn run(&mut self, (mut players, cars, dogs, muds, paused): Self::SystemData) {
+    fn run(
+        &mut self,
+        (players, contacts, obstacles, mut status_effects, paused, entities): Self::SystemData,
+    ) {
         if *paused == Paused::Paused {
             return;
         }
-        for (player,) in (&mut players,).join() {
-            // checks if player is hit by car
-            let mut hit_by_car: bool = false;
-            for (car,) in (&cars,).join() {
-                if car.is_player_touching {
-                    hit_by_car = true;
-                }
-            }
-            // checks if player is hit by dog
-            let mut hit_by_dog: bool = false;
-            for (dog,) in (&dogs,).join() {
-                if dog.is_player_touching {
-                    hit_by_dog = true;
-                }
-            }
-
-            // checks if player is hit by mud
-            let mut hit_by_mud: bool = false;
-            for (mud,) in (&muds,).join() {
-                if mud.is_player_touching {
-                    hit_by_mud = true;
-                }
-            }
-
-            // adjust player's speed bacsed on their collisions
-            if hit_by_dog {
-                player.stop();
-            } else if hit_by_mud {
-                player.slow_down();
-            } else {
-                player.normal_speed();
+        for (entity, _, contacts) in (&entities, &players, &contacts).join() {
+            let effects = status_effects
+                .entry(entity)
+                .expect("Tried to affect a dead entity")
+                .or_insert_with(Default::default);
+            // collects the effects of every obstacle this player is touching
+            let mut slowed = false;
+            for obstacle in contacts
+                .touching()
+                .iter()
+                .filter_map(|&obstacle| obstacles.get(obstacle))
+            {
+                slowed |= apply_contact_effects(effects, obstacle)
+                    .iter()
+                    .any(|effect| effect.kind == StatusKind::Slow);
             }
 
-            // checks if player hit by car
-            if hit_by_car {
-                player.is_in_car = true;
-            } else {
-                player.is_in_car = false;
+            // mud knocks the player off their skateboard
+            if slowed && effects.has(StatusKind::Boost) {
+                log::info!("Player lost their skateboard");
+                effects.remove(StatusKind::Boost);
             }
         }
     }
@@ -0,0 +1,255 @@
+// lets the player throw their phones at the enemy and at dogs
+use crate::{
+    components::{Dog, Enemy, Facing, Obstacle, Player, Projectile, StatusEffect, StatusEffects},
+    resources::{sprites::SpriteSheetRegister, GameplayAmmoDisplay, Paused, THROW_ACTION},
+};
+use amethyst::{
+    assets::AssetStorage,
+    core::{Time, Transform},
+    derive::SystemDesc,
+    ecs::{Entities, Join, Read, ReadStorage, System, SystemData, WriteStorage},
+    input::{InputHandler, StringBindings},
+    renderer::{SpriteRender, SpriteSheet},
+    ui::UiText,
+};
+use nalgebra::base::Vector3;
+
+/// The sprite sheet thrown phones are drawn from
+const PROJECTILE_SHEET_ID: &str = "phone";
+/// How much to scale up a thrown phone's sprite
+const PROJECTILE_SCALE: f32 = 3.0;
+/// How fast a thrown phone flies, in units per second
+const THROW_SPEED: f32 = 1200.0;
+/// How long a thrown phone flies before landing, in seconds
+const PROJECTILE_LIFETIME: f32 = 0.8;
+/// How close a thrown phone has to get to the enemy to hit them
+const HIT_RADIUS: f32 = 60.0;
+/// How long a hit stuns the enemy for, in seconds. Unlike being knocked
+/// out, a hit leaves the enemy open to another one straight away.
+const STUN_SECONDS: f32 = 1.5;
+/// How long a hit keeps a dog busy for, in seconds
+const DISTRACT_SECONDS: f32 = 3.0;
+/// How far away the enemy hears a thrown phone land
+const HEARING_RADIUS: f32 = 700.0;
+
+/// Makes the player throw a phone when the throw action is pressed, the
+/// way they are moving or else the way they are facing
+#[derive(Default)]
+pub struct ThrowSystem {
+    /// Whether the throw action was held last frame, so that holding it
+    /// down only throws once
+    held: bool,
+}
+
+impl<'s> System<'s> for ThrowSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        WriteStorage<'s, Player>,
+        ReadStorage<'s, Facing>,
+        WriteStorage<'s, Projectile>,
+        WriteStorage<'s, Transform>,
+        Read<'s, SpriteSheetRegister>,
+        Read<'s, AssetStorage<SpriteSheet>>,
+        WriteStorage<'s, SpriteRender>,
+        Read<'s, InputHandler<StringBindings>>,
+        Read<'s, Paused>,
+        Read<'s, Time>,
+        Entities<'s>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            mut players,
+            facings,
+            mut projectiles,
+            mut transforms,
+            spritesheet_registry,
+            spritesheet_storage,
+            mut sprite_renders,
+            input,
+            paused,
+            time,
+            entities,
+        ): Self::SystemData,
+    ) {
+        if *paused == Paused::Paused {
+            return;
+        }
+        let held = input.action_is_down(THROW_ACTION).unwrap_or(false);
+        let pressed = held && !self.held;
+        self.held = held;
+
+        let axis = |name: &str| input.axis_value(name).unwrap_or(0.0);
+        let aim = Vector3::new(
+            axis("horizontal") + axis("dpad_horizontal"),
+            axis("vertical") + axis("dpad_vertical"),
+            0.0f32,
+        );
+
+        let mut throws = Vec::new();
+        for (player, facing, transform) in (&mut players, facings.maybe(), &transforms).join() {
+            player.tick_throw(time.delta_seconds());
+            // players hiding in a car can't throw from it
+            if !pressed || player.is_hidden() || !player.throw() {
+                continue;
+            }
+            let direction = if aim.norm_squared() != 0.0 {
+                aim.normalize()
+            } else {
+                let facing = facing.cloned().unwrap_or_default();
+                Vector3::new(facing.direction(), 0.0, 0.0)
+            };
+            log::info!("Player threw a phone, {} left", player.ammo());
+            throws.push((*transform.translation(), direction));
+        }
+
+        for (position, direction) in throws {
+            let mut transform = Transform::default();
+            transform.set_translation(position);
+            *transform.scale_mut() *= PROJECTILE_SCALE;
+            let mut builder = entities
+                .build_entity()
+                .with(
+                    Projectile::new(direction * THROW_SPEED, PROJECTILE_LIFETIME),
+                    &mut projectiles,
+                )
+                .with(transform, &mut transforms);
+            // sprites aren't loaded when running without a window
+            if let Some(sprite_render) = spritesheet_registry.find_sprite_sans_world(
+                &spritesheet_storage,
+                PROJECTILE_SHEET_ID,
+                0,
+            ) {
+                builder = builder.with(sprite_render, &mut sprite_renders);
+            }
+            builder.build();
+        }
+    }
+}
+
+/// Flies thrown phones along, briefly stunning the enemy or distracting a dog
+/// when one hits them. A phone that lands without hitting anyone draws
+/// the enemy over to check out the noise.
+#[derive(SystemDesc)]
+pub struct ProjectileSystem;
+
+impl<'s> System<'s> for ProjectileSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        WriteStorage<'s, Projectile>,
+        WriteStorage<'s, Transform>,
+        WriteStorage<'s, Enemy>,
+        WriteStorage<'s, Dog>,
+        ReadStorage<'s, Obstacle>,
+        WriteStorage<'s, StatusEffects>,
+        Read<'s, Paused>,
+        Read<'s, Time>,
+        Entities<'s>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            mut projectiles,
+            mut transforms,
+            mut enemies,
+            mut dogs,
+            obstacles,
+            mut status_effects,
+            paused,
+            time,
+            entities,
+        ): Self::SystemData,
+    ) {
+        if *paused == Paused::Paused {
+            return;
+        }
+        let delta = time.delta_seconds();
+        let targets = (&entities, &enemies, &transforms)
+            .join()
+            .map(|(entity, _, transform)| (entity, *transform.translation()))
+            .collect::<Vec<_>>();
+        let dog_targets = (&entities, &dogs, &obstacles, &transforms)
+            .join()
+            .map(|(entity, _, obstacle, transform)| (entity, obstacle, *transform.translation()))
+            .collect::<Vec<_>>();
+
+        let mut distracted = Vec::new();
+        let mut landed = Vec::new();
+        for (projectile_entity, projectile, transform) in
+            (&entities, &mut projectiles, &mut transforms).join()
+        {
+            transform.prepend_translation(projectile.velocity * delta);
+            let position = *transform.translation();
+
+            if let Some(&(enemy, _)) = targets
+                .iter()
+                .find(|(_, target)| (position - target).xy().norm() <= HIT_RADIUS)
+            {
+                log::info!("A thrown phone hit the enemy");
+                status_effects
+                    .entry(enemy)
+                    .expect("Tried to affect a dead entity")
+                    .or_insert_with(Default::default)
+                    .apply(StatusEffect::stun(STUN_SECONDS));
+                entities
+                    .delete(projectile_entity)
+                    .expect("Error removing projectile");
+            } else if let Some(&(dog, ..)) =
+                dog_targets.iter().find(|(_, obstacle, dog_position)| {
+                    let offset = position - dog_position;
+                    obstacle.contains(offset.x, offset.y)
+                })
+            {
+                log::info!("A thrown phone distracted a dog");
+                distracted.push(dog);
+                entities
+                    .delete(projectile_entity)
+                    .expect("Error removing projectile");
+            } else if !projectile.tick(delta) {
+                landed.push(position);
+                entities
+                    .delete(projectile_entity)
+                    .expect("Error removing projectile");
+            }
+        }
+        for dog in distracted {
+            if let Some(dog) = dogs.get_mut(dog) {
+                dog.distract(DISTRACT_SECONDS);
+            }
+        }
+        for position in landed {
+            for (enemy, transform) in (&mut enemies, &transforms).join() {
+                if (position - transform.translation()).xy().norm() <= HEARING_RADIUS {
+                    log::info!("The enemy heard a thrown phone land");
+                    enemy.distract(position);
+                }
+            }
+        }
+    }
+}
+
+/// Shows how many phones the player has left on the HUD
+#[derive(Default)]
+pub struct AmmoDisplaySystem;
+
+impl<'s> System<'s> for AmmoDisplaySystem {
+    type SystemData = (
+        ReadStorage<'s, Player>,
+        Read<'s, GameplayAmmoDisplay>,
+        WriteStorage<'s, UiText>,
+    );
+
+    fn run(&mut self, (players, ammo_displays, mut uitext): Self::SystemData) {
+        let ammo = match (&players).join().next() {
+            Some(player) => format!("Phones: {}", player.ammo()),
+            None => return,
+        };
+        for &display in &ammo_displays.displays {
+            if let Some(ref mut text) = uitext.get_mut(display) {
+                text.text = ammo.clone();
+            }
+        }
+    }
+}
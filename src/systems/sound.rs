@@ -0,0 +1,103 @@
+// plays sound effects for things that happen during gameplay
+use crate::{
+    components::Player,
+    resources::{
+        audio::{SoundEffectRegistry, CAUGHT_SOUND},
+        GameEvent, Settings, MUTE_ACTION,
+    },
+};
+use amethyst::{
+    assets::AssetStorage,
+    audio::{output::Output, AudioSink, Source},
+    derive::SystemDesc,
+    ecs::{Read, ReadStorage, System, SystemData, Write},
+    input::{InputEvent, StringBindings},
+    shrev::{EventChannel, ReaderId},
+};
+
+#[derive(SystemDesc)]
+#[system_desc(name(SoundEffectSystemDesc))]
+pub struct SoundEffectSystem {
+    #[system_desc(event_channel_reader)]
+    reader: ReaderId<GameEvent>,
+}
+
+impl SoundEffectSystem {
+    pub fn new(reader: ReaderId<GameEvent>) -> Self {
+        SoundEffectSystem { reader }
+    }
+}
+
+impl<'s> System<'s> for SoundEffectSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Read<'s, EventChannel<GameEvent>>,
+        ReadStorage<'s, Player>,
+        Read<'s, SoundEffectRegistry>,
+        Read<'s, AssetStorage<Source>>,
+        Option<Read<'s, Output>>,
+        Read<'s, Settings>,
+    );
+
+    fn run(
+        &mut self,
+        (events, players, sound_effects, sources, output, settings): Self::SystemData,
+    ) {
+        for event in events.read(&mut self.reader) {
+            // only the player's contacts are worth hearing
+            let sound = match event {
+                GameEvent::ObstacleContact {
+                    character, kind, ..
+                } if players.contains(*character) => kind.as_str(),
+                GameEvent::ObstacleContact { .. } => continue,
+                GameEvent::Caught => CAUGHT_SOUND,
+            };
+            let source = sound_effects
+                .find_sans_world(sound)
+                .and_then(|handle| sources.get(&handle));
+            if let (Some(output), Some(source)) = (&output, source) {
+                output.play_once(source, settings.sfx_output_volume());
+            }
+        }
+    }
+}
+
+/// Mutes and unmutes all sound when the mute key is pressed, in any state
+#[derive(SystemDesc)]
+#[system_desc(name(MuteSystemDesc))]
+pub struct MuteSystem {
+    #[system_desc(event_channel_reader)]
+    reader: ReaderId<InputEvent<StringBindings>>,
+}
+
+impl MuteSystem {
+    pub fn new(reader: ReaderId<InputEvent<StringBindings>>) -> Self {
+        MuteSystem { reader }
+    }
+}
+
+impl<'s> System<'s> for MuteSystem {
+    type SystemData = (
+        Read<'s, EventChannel<InputEvent<StringBindings>>>,
+        Write<'s, Settings>,
+        Option<Write<'s, AudioSink>>,
+    );
+
+    fn run(&mut self, (events, mut settings, sink): Self::SystemData) {
+        let presses = events
+            .read(&mut self.reader)
+            .filter(|event| match event {
+                InputEvent::ActionPressed(action) => action == MUTE_ACTION,
+                _ => false,
+            })
+            .count();
+        if presses % 2 == 1 {
+            settings.muted = !settings.muted;
+            log::info!("Sound {}", if settings.muted { "muted" } else { "unmuted" });
+            if let Some(mut sink) = sink {
+                sink.set_volume(settings.music_output_volume());
+            }
+            settings.save();
+        }
+    }
+}
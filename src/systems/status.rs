@@ -0,0 +1,75 @@
+// counts down status effects and works out everyone's speed from them
+use crate::{
+    components::{Enemy, Obstacle, ObstacleEffect, Player, StatusEffect, StatusEffects},
+    resources::Paused,
+};
+use amethyst::{
+    core::Time,
+    derive::SystemDesc,
+    ecs::{Join, Read, System, SystemData, WriteStorage},
+};
+
+/// How long the effects of touching an obstacle linger after the character
+/// stops touching it. They are reapplied every frame until then.
+const CONTACT_EFFECT_SECONDS: f32 = 0.1;
+
+/// Apply the effects of touching `obstacle` for this frame, returning the
+/// effects which took hold
+pub(super) fn apply_contact_effects(
+    effects: &mut StatusEffects,
+    obstacle: &Obstacle,
+) -> Vec<StatusEffect> {
+    obstacle
+        .effects
+        .iter()
+        .filter_map(|effect| {
+            let (status, took_hold) = match effect {
+                ObstacleEffect::Slow => {
+                    let status = StatusEffect::slow(CONTACT_EFFECT_SECONDS);
+                    (status, effects.apply(status))
+                }
+                ObstacleEffect::Stop => {
+                    let status = StatusEffect::stun(CONTACT_EFFECT_SECONDS);
+                    (status, effects.apply(status))
+                }
+                ObstacleEffect::Stun(seconds) => {
+                    (StatusEffect::stun(*seconds), effects.knock_out(*seconds))
+                }
+                _ => return None,
+            };
+            if took_hold {
+                Some(status)
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+#[derive(SystemDesc)]
+pub struct StatusEffectSystem;
+
+impl<'s> System<'s> for StatusEffectSystem {
+    type SystemData = (
+        WriteStorage<'s, StatusEffects>,
+        WriteStorage<'s, Player>,
+        WriteStorage<'s, Enemy>,
+        Read<'s, Paused>,
+        Read<'s, Time>,
+    );
+
+    fn run(&mut self, (mut effects, mut players, mut enemies, paused, time): Self::SystemData) {
+        if *paused == Paused::Paused {
+            return;
+        }
+        for effects in (&mut effects).join() {
+            effects.tick(time.delta_seconds());
+        }
+        for (player, effects) in (&mut players, &effects).join() {
+            player.apply_effects(effects);
+        }
+        for (enemy, effects) in (&mut enemies, &effects).join() {
+            enemy.apply_effects(effects);
+        }
+    }
+}
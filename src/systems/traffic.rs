@@ -0,0 +1,171 @@
+// drives police cars along the traffic lanes across the street
+use crate::{
+    components::{Animation, Facing, Obstacle, ObstacleEffect, TrafficCar, TrafficLane},
+    resources::{
+        prefabs::{ObstaclePrefab, ObstaclePrefabRegistry},
+        sprites::SpriteSheetRegister,
+        GameRng, Paused,
+    },
+};
+use amethyst::{
+    assets::{AssetStorage, Handle, Prefab},
+    core::{Time, Transform},
+    derive::SystemDesc,
+    ecs::{Entities, Join, Read, System, SystemData, Write, WriteStorage},
+    renderer::{SpriteRender, SpriteSheet},
+};
+use rand::Rng;
+
+use super::obstacles::spawn_obstacle;
+
+/// How far from the middle of the street cars set off from and disappear
+/// at, which is just out of sight
+const TRAFFIC_EDGE: f32 = 1500.0;
+
+#[derive(SystemDesc)]
+pub struct TrafficSystem;
+
+impl<'s> System<'s> for TrafficSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        WriteStorage<'s, TrafficLane>,
+        WriteStorage<'s, TrafficCar>,
+        WriteStorage<'s, Obstacle>,
+        WriteStorage<'s, Facing>,
+        WriteStorage<'s, Animation>,
+        WriteStorage<'s, Transform>,
+        Read<'s, ObstaclePrefabRegistry>,
+        Read<'s, AssetStorage<Prefab<ObstaclePrefab>>>,
+        WriteStorage<'s, Handle<Prefab<ObstaclePrefab>>>,
+        Read<'s, SpriteSheetRegister>,
+        Read<'s, AssetStorage<SpriteSheet>>,
+        WriteStorage<'s, SpriteRender>,
+        Entities<'s>,
+        Read<'s, Paused>,
+        Read<'s, Time>,
+        Write<'s, GameRng>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            mut lanes,
+            mut cars,
+            mut obstacles,
+            mut facings,
+            mut animations,
+            mut transforms,
+            prefab_registry,
+            prefab_storage,
+            mut obstacle_prefab_handles,
+            spritesheet_registry,
+            spritesheet_storage,
+            mut sprite_render_storage,
+            entities,
+            paused,
+            time,
+            mut rng,
+        ): Self::SystemData,
+    ) {
+        if *paused == Paused::Paused {
+            return;
+        }
+        let delta = time.delta_seconds();
+
+        // sends the next car along each lane that is due one
+        let mut departures = Vec::new();
+        for (lane, transform) in (&mut lanes, &mut transforms).join() {
+            if !lane.is_started() {
+                // the road runs the whole way across the street, wherever
+                // the lane happened to be placed
+                transform.translation_mut().x = 0.0;
+                // staggers the lanes so their cars don't all set off together
+                let direction = if rng.gen() {
+                    Facing::Left
+                } else {
+                    Facing::Right
+                };
+                let delay = rng.gen::<f32>() * lane.interval;
+                lane.start(direction, delay);
+            }
+            if lane.tick(delta) {
+                departures.push((lane.clone(), transform.translation().y));
+            }
+        }
+        for (lane, y) in departures {
+            let definition = match prefab_registry.spawn_definition(&prefab_storage, &lane.car) {
+                Some(definition) => definition,
+                None => {
+                    log::warn!("Couldn't find car {} to drive along a lane", lane.car);
+                    continue;
+                }
+            };
+            let direction = lane.direction();
+            let car = spawn_obstacle(
+                &entities,
+                &mut transforms,
+                -direction.direction() * TRAFFIC_EDGE,
+                y,
+                definition,
+                &mut obstacle_prefab_handles,
+                &spritesheet_registry,
+                &spritesheet_storage,
+                &mut sprite_render_storage,
+            );
+            facings
+                .insert(car, direction)
+                .expect("Error turning car to face its lane");
+            cars.insert(car, TrafficCar::new(lane.speed))
+                .expect("Error adding car to traffic");
+        }
+
+        // parked cars and the like, which moving cars crash into
+        let blocking = (&entities, &obstacles, &transforms)
+            .join()
+            .filter(|(_, obstacle, _)| obstacle.has_effect(ObstacleEffect::Block))
+            .map(|(entity, obstacle, transform)| {
+                (
+                    entity,
+                    *transform.translation(),
+                    obstacle.width,
+                    obstacle.height,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        for (entity, car, facing, transform) in
+            (&entities, &mut cars, &facings, &mut transforms).join()
+        {
+            if car.is_wrecked() {
+                continue;
+            }
+            transform.prepend_translation_x(facing.direction() * car.speed * delta);
+            let position = *transform.translation();
+            // the car has driven out of sight
+            if position.x * facing.direction() > TRAFFIC_EDGE {
+                entities.delete(entity).expect("Error removing car");
+                continue;
+            }
+
+            // cars only get their hitbox once their prefab is applied
+            let obstacle = match obstacles.get_mut(entity) {
+                Some(obstacle) => obstacle,
+                None => continue,
+            };
+            let crashed = blocking
+                .iter()
+                .any(|&(other, other_position, width, height)| {
+                    let offset = position - other_position;
+                    other != entity
+                        && offset.x.abs() < obstacle.width + width
+                        && offset.y.abs() < obstacle.height + height
+                });
+            if crashed {
+                log::info!("A {} car crashed", obstacle.kind);
+                car.wreck(obstacle);
+                // the wreck stays on whatever frame it crashed on
+                animations.remove(entity);
+            }
+        }
+    }
+}
@@ -1,4 +1,11 @@
-use amethyst::{core::transform::ParentHierarchy, ecs::Entity, prelude::*};
+use crate::resources::CONFIRM_ACTION;
+use amethyst::{
+    core::transform::ParentHierarchy,
+    ecs::{Entity, Join},
+    input::InputEvent,
+    prelude::*,
+    ui::{Selected, TextEditing, UiEvent, UiEventType},
+};
 
 pub fn delete_hierarchy(world: &mut World, root: Entity) {
     let mut to_delete: Vec<Entity> = world
@@ -10,3 +17,31 @@ pub fn delete_hierarchy(world: &mut World, root: Entity) {
         .delete_entities(&to_delete)
         .unwrap_or_else(|e| log::warn!("Error removing heirarchy: {}", e));
 }
+
+/// Whether the event is the given input action being pressed
+pub fn is_action_down(event: &StateEvent, action: &str) -> bool {
+    match event {
+        StateEvent::Input(InputEvent::ActionPressed(pressed)) => pressed == action,
+        _ => false,
+    }
+}
+
+/// Turn the confirm button into a click on the selected button, or on
+/// `default` if no button is selected, so menus work with a controller
+pub fn confirm_as_click(world: &World, event: StateEvent, default: Option<Entity>) -> StateEvent {
+    if !is_action_down(&event, CONFIRM_ACTION) {
+        return event;
+    }
+    let selected = (
+        &world.entities(),
+        &world.read_storage::<Selected>(),
+        !&world.read_storage::<TextEditing>(),
+    )
+        .join()
+        .map(|(entity, _, _)| entity)
+        .next();
+    match selected.or(default) {
+        Some(target) => StateEvent::Ui(UiEvent::new(UiEventType::Click, target)),
+        None => event,
+    }
+}
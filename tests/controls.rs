@@ -0,0 +1,62 @@
+//! Checks switching between control schemes. Only the input resources are
+//! used, so no graphics backend is needed.
+
+use amethyst::{
+    input::{Bindings, Button, InputHandler, StringBindings, VirtualKeyCode},
+    prelude::*,
+};
+use game::resources::{
+    default_bindings, BindingTarget, Controls, Settings, JUMP_ACTION, REBINDABLE,
+};
+
+/// Every action and the combos bound to it, in a stable order
+fn action_combos(bindings: &Bindings<StringBindings>) -> Vec<(String, Vec<Vec<Button>>)> {
+    let mut actions = bindings
+        .actions()
+        .map(|action| {
+            let combos = bindings
+                .action_bindings(action)
+                .map(|combo| combo.to_vec())
+                .collect();
+            (action.clone(), combos)
+        })
+        .collect::<Vec<_>>();
+    actions.sort_by(|(a, _), (b, _)| a.cmp(b));
+    actions
+}
+
+#[test]
+fn reset_after_custom_and_preset_restores_default_actions() {
+    let defaults = default_bindings().expect("Couldn't load config/inputs.ron");
+    let mut world = World::new();
+    world.insert(InputHandler::<StringBindings>::new());
+
+    let mut custom = defaults.clone();
+    REBINDABLE
+        .iter()
+        .find(|rebindable| rebindable.target == BindingTarget::Action(JUMP_ACTION))
+        .expect("Jump isn't rebindable")
+        .bind(&mut custom, Button::Key(VirtualKeyCode::W))
+        .expect("Couldn't bind jump to W");
+    let mut settings = Settings::default();
+    settings.controls = Controls::Custom;
+    settings.custom_bindings = Some(custom);
+    settings.apply_controls(&mut world);
+
+    // pressing Controls twice goes through Arrows to WASD, which uses the W
+    // key the custom bindings gave to jump
+    for _ in 0..2 {
+        settings.controls = settings.controls.successor();
+        settings.apply_controls(&mut world);
+    }
+    assert_eq!(settings.controls, Controls::Wasd);
+    {
+        let input = world.read_resource::<InputHandler<StringBindings>>();
+        assert_eq!(action_combos(&input.bindings), action_combos(&defaults));
+    }
+
+    settings.reset_controls();
+    settings.apply_controls(&mut world);
+    let input = world.read_resource::<InputHandler<StringBindings>>();
+    assert_eq!(action_combos(&input.bindings), action_combos(&defaults));
+}
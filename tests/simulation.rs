@@ -0,0 +1,91 @@
+//! Checks gameplay through the headless simulation harness. Run with
+//! `cargo test --no-default-features --features empty` to avoid needing a
+//! graphics backend.
+
+use amethyst::{core::Transform, ecs::Join, prelude::*};
+use game::{
+    components::{Enemy, Obstacle, Player},
+    simulation::SimulationBuilder,
+};
+
+/// How far off a position can be from where it is expected, in units
+const TOLERANCE: f32 = 1.0;
+
+/// Runs far enough up the street for obstacles to be placed, weaving a
+/// little on the way
+const SCRIPT: &[(f32, f32, usize)] = &[(0.0, 1.0, 180), (0.5, 1.0, 60), (-0.5, 1.0, 120)];
+
+/// The kind and position of every obstacle after running `SCRIPT` on a
+/// level generated from `seed`
+fn obstacle_layout(seed: u64) -> Vec<(String, f32, f32)> {
+    let mut simulation = SimulationBuilder::new()
+        .with_seed(seed)
+        .build()
+        .expect("Failed to build simulation");
+    simulation.run_script(SCRIPT);
+    let world = simulation.world();
+    let obstacles = world.read_storage::<Obstacle>();
+    let transforms = world.read_storage::<Transform>();
+    (&obstacles, &transforms)
+        .join()
+        .map(|(obstacle, transform)| {
+            let translation = transform.translation();
+            (obstacle.kind.clone(), translation.x, translation.y)
+        })
+        .collect()
+}
+
+#[test]
+fn player_walks_up_at_their_speed() {
+    let mut simulation = SimulationBuilder::new()
+        .build()
+        .expect("Failed to build simulation");
+    let player = simulation.player();
+    let start = simulation
+        .world()
+        .read_storage::<Transform>()
+        .get(player)
+        .expect("Player has no transform")
+        .translation()
+        .y;
+    let speed = simulation
+        .world()
+        .read_storage::<Player>()
+        .get(player)
+        .expect("Player has no Player component")
+        .effective_speed();
+
+    // a second at 60 frames per second, well short of where obstacles start
+    simulation.set_axes(0.0, 1.0);
+    simulation.run(60);
+
+    let end = simulation
+        .world()
+        .read_storage::<Transform>()
+        .get(player)
+        .expect("Player has no transform")
+        .translation()
+        .y;
+    assert!(
+        (end - start - speed).abs() <= TOLERANCE,
+        "Player moved {} units in a second at {} units per second",
+        end - start,
+        speed
+    );
+
+    let enemy = simulation.enemy();
+    assert!(simulation.world().is_alive(enemy));
+    assert!(simulation
+        .world()
+        .read_storage::<Enemy>()
+        .get(enemy)
+        .is_some());
+}
+
+#[test]
+fn same_seed_and_inputs_give_the_same_obstacles() {
+    let layout = obstacle_layout(42);
+    assert!(!layout.is_empty(), "No obstacles were placed");
+    assert_eq!(layout, obstacle_layout(42));
+    assert_ne!(layout, obstacle_layout(43));
+}